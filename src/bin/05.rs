@@ -1,49 +1,35 @@
-use std::cmp::Ordering::{Greater, Less};
-use std::collections::{HashMap, HashSet};
+use advent_of_code::utils::graph::DiGraph;
 
 advent_of_code::solution!(5);
 
-fn split_file(input: &str) -> (HashMap<u32, Vec<u32>>, Vec<Vec<u32>>) {
-    let parts: Vec<&str> = input.split("\n\n").collect();
+// A rule `a|b` becomes the edge `a -> b`: page a must be printed before page b
+fn split_file(input: &str) -> (DiGraph<u32>, Vec<Vec<u32>>) {
+    let (rules, updates) = input.split_once("\n\n").unwrap();
 
-    let page_ordering_rules: HashMap<u32, Vec<u32>> = parts[0].lines()
-        .map(|line| {
-            let mut pair_itr = line.split('|')
-                .map(|n| n.parse::<u32>().unwrap());
-            let val = pair_itr.next().unwrap();
-            let key = pair_itr.next().unwrap();
+    let page_ordering_rules = DiGraph::from_edge_list(rules, '|', |n| n.parse::<u32>().unwrap());
 
-            (key, val)
-        })
-        .fold(HashMap::new(), |mut map, (key, val)| {
-            map.entry(key).or_insert_with(Vec::new).push(val);
-            map
-        });
-
-    let update_page_numbers: Vec<Vec<u32>> = parts[1]
+    let update_page_numbers: Vec<Vec<u32>> = updates
         .lines()
         .map(|l| l.split(',')
             .map(|n| n.parse::<u32>().unwrap())
             .collect())
         .collect();
 
-    (page_ordering_rules.into_iter().collect(), update_page_numbers)
+    (page_ordering_rules, update_page_numbers)
 }
 
-fn is_update_valid(input: &Vec<u32>, rules: &HashMap<u32, Vec<u32>>) -> bool {
-    let mut checked: HashSet<u32> = HashSet::new();
-    let mut to_check: Vec<u32> = input.to_vec();
-
-    while let Some(page) = to_check.pop() {
-        if let Some(values) = rules.get(&page) {
-            if values.iter().any(|&value| checked.contains(&value)) {
-                return false;
-            }
-        }
-        checked.insert(page);
-    }
+// In order when no rule asks for a page to come before one printed earlier. Pages without a
+// rule between them can come in any order
+fn is_ordered(update: &[u32], rules: &DiGraph<u32>) -> bool {
+    update.iter().enumerate()
+        .all(|(i, before)| update[i + 1..].iter().all(|after| !rules.has_edge(after, before)))
+}
 
-    true
+// One order of the update's pages following the rules, to fix the updates out of order
+fn ordered_update(update: &[u32], rules: &DiGraph<u32>) -> Vec<u32> {
+    rules.subgraph(update)
+        .topological_sort()
+        .expect("rules should not be contradictory within an update")
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -51,7 +37,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     let mut result: u32 = 0;
     for update in update_page_numbers {
-        if is_update_valid(&update, &page_ordering_rules) {
+        if is_ordered(&update, &page_ordering_rules) {
             result += update[update.len() / 2];
         }
     }
@@ -59,24 +45,13 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(result)
 }
 
-fn reorder_update(input: &Vec<u32>, rules: &HashMap<u32, Vec<u32>>) -> Vec<u32> {
-    let mut to_sort = input.clone();
-    to_sort.sort_by(|a, b| {
-        match rules.get(a) {
-            Some(value) if value.contains(b) => Greater,
-            _ => Less,
-        }
-    });
-    to_sort
-}
-
 pub fn part_two(input: &str) -> Option<u32> {
     let (page_ordering_rules, update_page_numbers) = split_file(input);
 
     let mut result: u32 = 0;
     for update in update_page_numbers {
-        if !is_update_valid(&update, &page_ordering_rules) {
-            let ordered_update: Vec<u32> = reorder_update(&update, &page_ordering_rules);
+        if !is_ordered(&update, &page_ordering_rules) {
+            let ordered_update = ordered_update(&update, &page_ordering_rules);
             result += ordered_update[ordered_update.len() / 2];
         }
     }
@@ -90,13 +65,22 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(143));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(123));
     }
+
+    #[test]
+    fn test_partial_rules() {
+        // No rule between 1 and 3, so both updates are already in order
+        let input = "1|2\n3|2\n\n3,1,2\n1,3,2\n2,1,3\n";
+        assert_eq!(part_one(input), Some(1 + 3));
+        // 2,1,3 is fixed with 2 last, the middle page being either of the others
+        assert!(matches!(part_two(input), Some(1 | 3)));
+    }
 }
@@ -1,18 +1,20 @@
-use std::collections::HashSet;
+use advent_of_code::utils::bitset::DirectionalGridBitSet;
+use advent_of_code::utils::grid_utils::{get_value_from_grid_pos, Direction, Pos};
+use advent_of_code::utils::simulation::Simulation;
 
 advent_of_code::solution!(6);
 
 
 // Parse and find the start pos:
-fn parse_input (input: &str) -> (Vec<Vec<char>>, (i32, i32)) {
+fn parse_input (input: &str) -> (Vec<Vec<char>>, Pos) {
     let mut matrix: Vec<Vec<char>> = Vec::new();
-    let mut start: (i32, i32) = (0, 0);
+    let mut start = Pos::new(0, 0);
 
     for (i, line) in input.lines().enumerate() {
         let row: Vec<char> = line.chars().collect();
         for (j, &ch) in row.iter().enumerate() {
             if ch == '^' {
-                start = (i as i32, j as i32);
+                start = Pos::new(i as isize, j as isize);
             }
         }
         matrix.push(row);
@@ -22,82 +24,90 @@ fn parse_input (input: &str) -> (Vec<Vec<char>>, (i32, i32)) {
 }
 
 // Move in a direction, until the guard leaves the matrix, or hit an obstacle
-fn move_direction(matrix: &mut Vec<Vec<char>>, pos: (i32, i32), dir: (i32, i32)) -> ((i32, i32), i32, bool, Vec<(i32, i32)>) {
+fn move_direction(matrix: &mut [Vec<char>], pos: Pos, dir: Direction) -> (Pos, i32, bool, Vec<Pos>) {
     let mut move_count = 0;
-    let mut new_pos = pos.clone();
-    let rows = matrix.len() as i32;
-    let cols = matrix[0].len() as i32;
+    let mut new_pos = pos;
     // For part two, register all possible pos for an obstacle
-    let mut possible_obstacles: Vec<(i32, i32)> = Vec::new();
+    let mut possible_obstacles: Vec<Pos> = Vec::new();
 
     loop {
-        let old_pos = new_pos.clone();
-        new_pos = (old_pos.0 + dir.0, old_pos.1 + dir.1);
-
-        if !(new_pos.0 >= 0 && new_pos.0 < rows && new_pos.1 >= 0 && new_pos.1 < cols) {
-            return ((new_pos.0, new_pos.1), move_count, false, possible_obstacles)
-        } else {
-            let value = matrix[new_pos.0 as usize][new_pos.1 as usize];
-            if value == '#' {
-                return (old_pos, move_count, true, possible_obstacles)
-            } else if value != 'X' {
+        let old_pos = new_pos;
+        new_pos = old_pos.neighbor(dir);
+
+        match get_value_from_grid_pos(matrix, new_pos).copied() {
+            None => return (new_pos, move_count, false, possible_obstacles),
+            Some('#') => return (old_pos, move_count, true, possible_obstacles),
+            Some('X') => {}
+            Some(_) => {
                 // I was too lazy to create a set just for that,
                 // instead I updated the value on the matrix (maybe I'll change to a set)
                 possible_obstacles.push(new_pos);
-                move_count = move_count + 1;
-                matrix[new_pos.0 as usize][new_pos.1 as usize] = 'X';
+                move_count += 1;
+                matrix[new_pos.row as usize][new_pos.col as usize] = 'X';
             }
         }
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (mut matrix, pos) = parse_input(input);
-    let mut count = 0;
+// The guard's walk, one straight line until an obstacle or the edge per step
+#[derive(Clone)]
+struct Patrol {
+    matrix: Vec<Vec<char>>,
+    pos: Pos,
+    dir: Direction,
+    count: i32,
+    possible_obstacles: Vec<Pos>,
+    inside: bool,
+}
 
-    // Directions UP, RIGHT, DOWN, LEFT
-    let dirs: Vec<(i32, i32)> = vec![(-1,0), (0,1),(1,0),(0,-1)];
+fn walk_leg(patrol: &mut Patrol) {
+    let (pos, count, inside, possible_obstacles) = move_direction(&mut patrol.matrix, patrol.pos, patrol.dir);
+    patrol.dir = patrol.dir.turn_right();
+    patrol.pos = pos;
+    patrol.count += count;
+    patrol.possible_obstacles.extend(possible_obstacles);
+    patrol.inside = inside;
+}
 
-    let mut current_dir = 0;
-    let mut current_pos = pos.clone();
-    loop {
-        let result = move_direction(&mut matrix, current_pos, dirs[current_dir]);
-        // Get next direction modulo 4 ^^
-        current_dir = (current_dir + 1) % dirs.len();
-        current_pos = result.0;
-        count = count + result.1;
+// Walks until the guard leaves the matrix, marking the visited cells with 'X'
+fn patrol(matrix: Vec<Vec<char>>, start: Pos) -> Patrol {
+    // Every leg ends on a different cell and direction, unless the guard loops
+    let max_legs = 4 * matrix.len() * matrix.first().map_or(0, |row| row.len());
+    let initial = Patrol { matrix, pos: start, dir: Direction::North, count: 0, possible_obstacles: Vec::new(), inside: true };
+
+    let mut simulation = Simulation::in_place(initial, walk_leg);
+    simulation.run_until(max_legs, |patrol| !patrol.inside).expect("the guard should leave the lab");
+    simulation.into_state()
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let (matrix, pos) = parse_input(input);
+    let count = patrol(matrix, pos).count;
 
-        if !result.2 {
-            break
-        }
-    }
     // I don't understand why I have to + 1 here don't ask :trollface:
     // without the + 1 it was ok with the sample, but not the real solution
     Some((count + 1) as u32)
 }
 
-fn find_infinite_loops(matrix: &mut Vec<Vec<char>>, pos: (i32, i32), obs: (i32, i32)) -> bool {
-    let dirs: Vec<(i32, i32)> = vec![(-1,0), (0,1),(1,0),(0,-1)];
-
+fn find_infinite_loops(matrix: &[Vec<char>], pos: Pos, obs: Pos) -> bool {
     // Put the obstacle
-    let mut clone = matrix.clone();
-    clone[obs.0 as usize][obs.1 as usize] = '#';
+    let mut clone = matrix.to_vec();
+    clone[obs.row as usize][obs.col as usize] = '#';
 
-    let mut current_dir = 0;
-    let mut current_pos = pos.clone();
+    let mut current_dir = Direction::North;
+    let mut current_pos = pos;
 
     // Store the move direction done to identify repeats
-    let mut operations: HashSet<((i32, i32), (i32, i32))> = HashSet::new();
+    let mut operations = DirectionalGridBitSet::for_grid(matrix);
 
     loop {
         // Infinite loop found
-        if operations.contains(&(current_pos, dirs[current_dir])) {
+        if !operations.insert(current_pos, current_dir) {
             return true;
         }
-        operations.insert((current_pos, dirs[current_dir]));
 
-        let result = move_direction(&mut clone, current_pos, dirs[current_dir]);
-        current_dir = (current_dir + 1) % dirs.len();
+        let result = move_direction(&mut clone, current_pos, current_dir);
+        current_dir = current_dir.turn_right();
         current_pos = result.0;
 
         if !result.2 {
@@ -107,27 +117,12 @@ fn find_infinite_loops(matrix: &mut Vec<Vec<char>>, pos: (i32, i32), obs: (i32,
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (mut matrix, pos) = parse_input(input);
-
-    let dirs: Vec<(i32, i32)> = vec![(-1,0), (0,1),(1,0),(0,-1)];
-    let mut possible_blocs: Vec<(i32, i32)> = Vec::new();
-
-    let mut current_dir = 0;
-    let mut current_pos = pos.clone();
-    loop {
-        let result = move_direction(&mut matrix, current_pos, dirs[current_dir]);
-        current_dir = (current_dir + 1) % dirs.len();
-        possible_blocs.extend(result.3);
-        current_pos = result.0;
-
-        if !result.2 {
-            break
-        }
-    }
+    let (matrix, pos) = parse_input(input);
+    let Patrol { matrix, possible_obstacles: possible_blocs, .. } = patrol(matrix, pos);
 
     let mut count = 0;
     for possible_bloc_pos in possible_blocs.iter() {
-        if find_infinite_loops(&mut matrix, pos, *possible_bloc_pos) {
+        if find_infinite_loops(&matrix, pos, *possible_bloc_pos) {
             count = count  + 1;
         }
     }
@@ -141,13 +136,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(42));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(6));
     }
 }
@@ -1,147 +1,72 @@
+use std::ops::Range;
+
+use advent_of_code::utils::ranges::{IntervalMap, RangeSet};
+
 advent_of_code::solution!(9);
 
 fn parse(input: &str) -> Vec<u32> {
     input.trim().chars().map(|c| c.to_digit(10).unwrap()).collect()
 }
 
-#[derive(Debug)]
-struct Block {
-    file_id: usize,
-    index: usize,
+// The span of each file with its id, and the free space in between
+fn layout(sizes: &[u32]) -> (Vec<(usize, Range<usize>)>, RangeSet<usize>) {
+    let mut files = Vec::new();
+    let mut free = RangeSet::new();
+    let mut index = 0;
+
+    for (i, &size) in sizes.iter().enumerate() {
+        let span = index..index + size as usize;
+        if i % 2 == 0 {
+            files.push((i / 2, span));
+        } else {
+            free.insert(span);
+        }
+        index += size as usize;
+    }
+
+    (files, free)
 }
 
-fn get_blocks(index: usize, count: &u32, file_id: usize) -> Vec<Block> {
-    (index.. *count as usize + index)
-        .map(|i| Block { file_id, index: i })
-        .collect()
+fn checksum(disk: &IntervalMap<usize, usize>) -> usize {
+    disk.iter()
+        .map(|(span, &file_id)| span.sum::<usize>() * file_id)
+        .sum()
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let spaces = parse(input);
-
-    let mut indexes: usize = 0;
-    let mut free_spaces: Vec<usize> = Vec::new();
-    let mut file_id = 0;
-
-    let mut blocks: Vec<Block> = Vec::new();
-
-    spaces.iter().enumerate().for_each(
-        |(index, count)| {
-            let new_vec = get_blocks(indexes, count, file_id);
-
-            match index % 2 {
-                1 => {
-                    let free_indexes: Vec<usize> = new_vec.iter().map(|b| b.index).collect();
-                    free_spaces.extend(free_indexes);
-                    indexes += *count as usize;
-                },
-                0 => {
-                    blocks.extend(new_vec);
-                    indexes += *count as usize;
-                    file_id += 1;
-                },
-                _ => unimplemented!()
-            }
-        }
-    );
-
-    free_spaces.sort_by(|a, b| b.cmp(a));
-
-    blocks.iter_mut().rev().for_each(
-        |b| {
-            if let Some(free_block) = free_spaces.last() {
-                if *free_block < b.index {
-                    b.index = *free_block;
-                    free_spaces.pop();
-                }
-            }
+    let (files, mut free) = layout(&parse(input));
+    let mut disk = IntervalMap::new();
+
+    // Blocks move from the end of the disk to the leftmost free block, filling a whole free
+    // span at once with the end of a file
+    for (file_id, span) in files.iter().rev() {
+        let mut remaining = span.clone();
+        while !remaining.is_empty() {
+            let Some(gap) = free.iter().next().filter(|gap| gap.start < remaining.start) else {
+                break;
+            };
+            let moved = gap.start..gap.start + gap.len().min(remaining.len());
+            remaining.end -= moved.len();
+            free.remove(moved.clone());
+            disk.insert(moved, *file_id);
         }
-    );
-
-    Some(blocks.iter()
-        .map(|b| {
-            return b.index * b.file_id;
-        }).sum())
-}
-
-#[derive(Debug)]
-struct BlockPartTwo {
-    first_index: usize,
-    indexes: Vec<usize>,
-    file_id: usize,
-}
+        disk.insert(remaining, *file_id);
+    }
 
-fn get_block_part_two(index: usize, count: &u32, file_id: usize) -> BlockPartTwo {
-    let indexes = (index.. *count as usize + index).collect();
-    BlockPartTwo { first_index: index, indexes, file_id }
+    Some(checksum(&disk))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let spaces = parse(input);
-
-    let mut indexes: usize = 0;
-    let mut free_spaces: Vec<Vec<usize>> = Vec::new();
-    let mut file_id = 0;
-
-    let mut blocks: Vec<BlockPartTwo> = Vec::new();
-
-    spaces.iter().enumerate().for_each(
-        |(index, count)| {
-            let block = get_block_part_two(indexes, count, file_id);
-
-            match index % 2 {
-                1 => {
-                    let mut free_indexes: Vec<usize> = block.indexes;
-                    // sort to ease popping out element
-                    free_indexes.sort_by(|a, b| b.cmp(a));
-                    free_spaces.push(free_indexes);
-                    indexes += *count as usize;
-                },
-                0 => {
-                    blocks.push(block);
-                    indexes += *count as usize;
-                    file_id += 1;
-                },
-                _ => unimplemented!()
-            }
-        }
-    );
-
-    blocks.iter_mut().rev().for_each(
-        |b| {
-            for free_block in free_spaces.iter_mut() {
-                if free_block.len() >= b.indexes.len() {
-                    if free_block.last().unwrap() > &b.first_index {
-                        continue;
-                    }
-
-                    // extract free blocks
-                    let mut new_indexes: Vec<usize> = Vec::new();
-
-                    for _i in 0..b.indexes.len() {
-                        new_indexes.push(free_block.pop().unwrap());
-                    }
-                    b.first_index = new_indexes[0];
-                    b.indexes = new_indexes;
-                    break;
-                }
-            }
-        }
-    );
-
-    blocks.sort_by(|a, b| a.first_index.cmp(&b.first_index));
+    let (files, mut free) = layout(&parse(input));
+    let mut disk = IntervalMap::new();
 
-    let mut result: usize = 0;
-    blocks.iter()
-        .flat_map(|b| {
-            let blocks: Vec<Block> = b.indexes.iter().map(|i| Block { index: *i, file_id: b.file_id }).collect();
-            return blocks;
-        })
-        .for_each(|b| {
-            result += b.index * b.file_id;
-        });
+    // Whole files move once, to the leftmost free span large enough before them
+    for (file_id, span) in files.iter().rev() {
+        let target = free.first_fit(span.len(), span.start).unwrap_or(span.clone());
+        disk.insert(target, *file_id);
+    }
 
-    Some(result)
+    Some(checksum(&disk))
 }
 
 #[cfg(test)]
@@ -150,13 +75,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(1928));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(2858));
     }
 }
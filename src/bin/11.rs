@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use advent_of_code::utils::memo::Memoized;
 
 advent_of_code::solution!(11);
 
@@ -18,31 +18,25 @@ fn apply_rule(stone: i64) -> (i64, Option<i64>) {
     }
 }
 
-fn iterate_stones(stones: Vec<i64>, iteration_count: i8) -> i64 {
-    let mut stone_map = stones.iter().map(|s| (*s, 1_i64)).collect();
-
-    for _ in 0..iteration_count {
-        let mut new_stones_map: HashMap<i64, i64> = HashMap::new();
-
-        // For each stone, apply rule and add to map the new stone(s) and counts
-        for (stone, count) in stone_map {
-            let result = apply_rule(stone);
-            *new_stones_map.entry(result.0).or_default() += count;
-            if result.1.is_some() {
-                *new_stones_map.entry(result.1.unwrap()).or_default() += count;
-            }
+fn iterate_stones(stones: Vec<i64>, iteration_count: u8) -> i64 {
+    // Number of stones a stone turns into after blinking, the same stones coming up again and again
+    let mut blink = Memoized::new(|blink: &mut dyn FnMut((i64, u8)) -> i64, (stone, blinks)| {
+        if blinks == 0 {
+            return 1;
         }
+        match apply_rule(stone) {
+            (left, Some(right)) => blink((left, blinks - 1)) + blink((right, blinks - 1)),
+            (next, None) => blink((next, blinks - 1)),
+        }
+    });
 
-        stone_map = new_stones_map;
-    }
-
-    stone_map.values().sum()
+    stones.into_iter().map(|stone| blink.call((stone, iteration_count))).sum()
 }
 
 pub fn part_one(input: &str) -> Option<i64> {
     let stones = parse(input);
 
-    let result = iterate_stones(stones, 25_i8);
+    let result = iterate_stones(stones, 25);
 
     Some(result)
 }
@@ -50,7 +44,7 @@ pub fn part_one(input: &str) -> Option<i64> {
 pub fn part_two(input: &str) -> Option<i64> {
     let stones = parse(input);
 
-    let result = iterate_stones(stones, 75_i8);
+    let result = iterate_stones(stones, 75);
 
     Some(result)
 }
@@ -61,22 +55,21 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(55312));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(65601038650482));
     }
 }
 
 // Awesome stuff learned today:
-// Map stuff:
-// * Vec to HashMap using (<key>, <val>) mapping: <vec>.iter().map(|s| (<key>,<val>)).collect();
-// * Map or_default() automatically fill value with type
-// * Increment value in map: *<var>.entry(<key>).or_default() += count;
+// Memo stuff:
+// * Memoized recursion: the closure gets itself back as `&mut dyn FnMut(<key>) -> <val>` to recurse
+// * Key on everything the result depends on, here (<stone>, <blinks left>)
 //
 // Numeric stuff:
 // use power of tens to identify digit count in number:  <number>.checked_<type>log10().unwrap_or(0)
@@ -1,77 +1,51 @@
-use itertools::Itertools;
+use std::str::FromStr;
+
+use advent_of_code::template::Parsed;
+use advent_of_code::utils::math::min_cost_combination;
+use advent_of_code::utils::parse::{ints_tuple, sections, ParseError};
 
 advent_of_code::solution!(13);
 
+#[derive(Clone)]
 struct Machine {
-    button_a: (i32, i32),
-    button_b: (i32, i32),
-    prize: (i32, i32),
-}
-
-fn parse(input: &str) -> Vec<Machine> {
-    // Split per machine, then fetch tuple of coordinates
-    input.split("\n\n").map(|l| {
-        let (x1, x2, y1, y2, z1, z2) = l
-            .split(|c: char| !c.is_ascii_digit())
-            .filter(|w| !w.is_empty())
-            .map(|w| w.parse().unwrap())
-            .collect_tuple()
-            .unwrap();
-        Machine {
-            button_a: (x1, y1),
-            button_b: (x2, y2),
-            prize: (z1, z2),
-        }
-    }).collect()
+    button_a: (i64, i64),
+    button_b: (i64, i64),
+    prize: (i64, i64),
 }
 
-// https://en.wikipedia.org/wiki/Cramer%27s_rule
-fn solve(x1: i64, x2: i64, y1: i64, y2: i64, z1: i64, z2: i64) -> i64 {
-    let b = (z2 * x1 - z1 * x2) / (y2 * x1 - y1 * x2);
-    let a = (z1 - b * y1) / x1;
-    if (x1 * a + y1 * b, x2 * a + y2 * b) != (z1, z2) {
-        // No solution
-        return 0;
+#[derive(Clone)]
+pub struct Machines(Vec<Machine>);
+
+impl FromStr for Machines {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // One machine per section, then fetch tuple of coordinates
+        sections(input).iter().map(|section| section.parse(|text| {
+            let (ax, ay, bx, by, px, py) = ints_tuple(text)?;
+            Ok(Machine {
+                button_a: (ax, ay),
+                button_b: (bx, by),
+                prize: (px, py),
+            })
+        })).collect::<Result<_, _>>().map(Machines)
     }
-    // Calculate price (3 * button a + button b)
-    a * 3 + b
 }
 
-pub fn part_one(input: &str) -> Option<i64> {
-    let machines = parse(input);
+// Cheapest presses of A (3 tokens) and B (1 token) reaching the prize, 0 when there are none
+fn tokens(machine: &Machine, offset: i64) -> i64 {
+    let (px, py) = machine.prize;
 
-    let mut result = 0;
-    for machine in machines.iter() {
-        // Extract coefficients
-        let (x1, y1) = machine.button_a;
-        let (x2, y2) = machine.button_b;
-        let (z1, z2) = machine.prize;
-
-        result += solve(x1 as i64, x2 as i64, y1 as i64, y2 as i64, z1 as i64, z2 as i64)
-
-    }
-
-    Some(result) // Return a placeholder value for now
+    min_cost_combination(machine.button_a, machine.button_b, (px + offset, py + offset), (3, 1))
+        .map_or(0, |(a, b)| a * 3 + b)
 }
 
-pub fn part_two(input: &str) -> Option<i64> {
-    let machines = parse(input);
-
-    let mut result = 0;
-    for machine in machines.iter() {
-        // Extract coefficients
-        let (x1, y1) = machine.button_a;
-        let (x2, y2) = machine.button_b;
-        let (z1, z2) = machine.prize;
-        let (fixed_z1, fixed_z2) = (
-            z1 as i64 + 10000000000000i64,
-            z2 as i64 + 10000000000000i64
-        );
-        result += solve(x1 as i64, x2 as i64, y1 as i64, y2 as i64, fixed_z1, fixed_z2)
-
-    }
+pub fn part_one(Parsed(Machines(machines)): Parsed<Machines>) -> Option<i64> {
+    Some(machines.iter().map(|machine| tokens(machine, 0)).sum())
+}
 
-    Some(result) // Return a placeholder value for now
+pub fn part_two(Parsed(Machines(machines)): Parsed<Machines>) -> Option<i64> {
+    Some(machines.iter().map(|machine| tokens(machine, 10000000000000)).sum())
 }
 
 #[cfg(test)]
@@ -80,13 +54,15 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY).unwrap();
+        let result = part_one(Parsed(input.parse().unwrap()));
         assert_eq!(result, Some(480));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY).unwrap();
+        let result = part_two(Parsed(input.parse().unwrap()));
         assert_eq!(result, Some(875318608908));
     }
 }
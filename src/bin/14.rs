@@ -1,53 +1,51 @@
 use std::collections::HashMap;
-use std::ops::Mul;
 use std::cmp::Ordering::*;
-use crate::utils::Pos;
+use advent_of_code::utils::grid::{Grid, TorusGrid};
+use advent_of_code::utils::parse::ints_array;
+use advent_of_code::utils::math::crt;
+use advent_of_code::utils::image::{ImageExport, Palette};
+use advent_of_code::utils::recorder::Recorder;
+use advent_of_code::utils::render::Render;
+use advent_of_code::utils::simulation::Simulation;
+use advent_of_code::utils::grid_utils::Pos;
 
-#[path = "utils/grid_utils.rs"] mod utils;
-
-advent_of_code::solution!(14);
+advent_of_code::solution!(14, record = robot_frames);
 
 fn parse(input: &str) -> Vec<(Pos, Pos)> {
-    input.lines().map(
-        |l| {
-            let parts: Vec<isize> = l.split(|c: char| !c.is_ascii_digit() && c != '-')
-                .filter(|s| !s.is_empty())
-                .map(|s| s.parse().unwrap())
-                .collect();
-
-            (Pos::new(parts[0], parts[1]), Pos::new(parts[2], parts[3]))
+    input.lines().enumerate().map(
+        |(i, l)| {
+            let [x, y, vx, vy] = ints_array(l).unwrap_or_else(|e| panic!("Invalid input, {}", e.shifted(i)));
+
+            (Pos::from_xy(x as isize, y as isize), Pos::from_xy(vx as isize, vy as isize))
         }
     ).collect()
 }
 
-fn move_robot(robot: &(Pos, Pos), dimensions: (usize, usize), time: usize) -> Pos {
+// The floor wraps around its edges, so robots walking off one side come back on the other
+fn move_robot<T>(robot: &(Pos, Pos), floor: &TorusGrid<T>, time: usize) -> Pos {
     let (initial_pos, vec) = robot;
-    let (max_x, max_y) = dimensions;
-
-    let new_x = (initial_pos.x + vec.x.mul(time as isize)).rem_euclid(max_x as isize);
-    let new_y = (initial_pos.y + vec.y.mul(time as isize)).rem_euclid(max_y as isize);
 
-    Pos::new(new_x, new_y)
+    floor.wrap(*initial_pos + *vec * time as isize)
 }
 
 fn get_quadrant_id(pos: Pos, width: usize, height: usize) -> Option<usize> {
     let height_quadrant = ((height- 1) / 2) as isize;
     let width_quadrant = ((width- 1) / 2) as isize;
 
-    if pos.x == width_quadrant && pos.y == height_quadrant {
+    if pos.col == width_quadrant && pos.row == height_quadrant {
         return None;
     }
 
-    if pos.x > width_quadrant {
-        if pos.y > height_quadrant {
+    if pos.col > width_quadrant {
+        if pos.row > height_quadrant {
             return Some(3);
-        } else if pos.y < height_quadrant {
+        } else if pos.row < height_quadrant {
             return Some(1);
         }
-    } else if pos.x < width_quadrant {
-        if pos.y > height_quadrant {
+    } else if pos.col < width_quadrant {
+        if pos.row > height_quadrant {
             return Some(2);
-        } else if pos.y < height_quadrant {
+        } else if pos.row < height_quadrant {
             return Some(0)
         }
     }
@@ -56,12 +54,12 @@ fn get_quadrant_id(pos: Pos, width: usize, height: usize) -> Option<usize> {
 
 pub fn part_one_with_params(input: &str, width: usize, height: usize, time: usize) -> Option<usize> {
     let robots = parse(input);
-
+    let floor = TorusGrid::new(width, height, ());
 
     let mut result_pos_map: HashMap<usize, usize> = HashMap::new();
     robots.iter().for_each(
         |robot| {
-            let new_pos = move_robot(robot, (width, height), time);
+            let new_pos = move_robot(robot, &floor, time);
 
             let quadrant_id = get_quadrant_id(new_pos, width, height);
 
@@ -78,57 +76,50 @@ pub fn part_one(input: &str) -> Option<usize> {
     part_one_with_params(input, 101, 103, 100)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    // The first approach was to output the first 250 results in a file
-    // A first pattern found (Vertical) at 2, then 103, 204, etc.
-    // Horizontal pattern where starting at 75, then 178, etc.
-    // I estimated around 7670-80 the tree should appear.
-    // It did not work, so I printed all pattern for time < 10 000 seconds
-    // and got it right by analyzing the results around there,
-    let mut robots = parse(input);
-    let mut i = 0;
-
-    let robot_count = robots.len();
-    loop {
-        i += 1;
-
-        let mut new_robots: Vec<(Pos, Pos)> = Vec::new();
-        let mut result_pos_map: HashMap<Pos, usize> = HashMap::new();
-
-        robots.iter().for_each(
-            |robot| {
-                let new_robot = move_robot(robot, (101, 103), 1);
-                *result_pos_map.entry(new_robot).or_default() += 1;
-                new_robots.push((new_robot, robot.1));
-            }
-        );
-
-        // The robots are aligning, and seems like when they do so they dont overlap!
-        if result_pos_map.keys().len() == robot_count {
-            break;
-        }
-
-        robots = new_robots.clone();
-    }
-
-    Some(i)
+pub fn part_two_with_params(input: &str, width: usize, height: usize) -> Option<u32> {
+    let robots = parse(input);
+    let floor: TorusGrid<()> = TorusGrid::new(width, height, ());
+
+    // Columns come back every `width` seconds and rows every `height` seconds. The tree is
+    // drawn when both are the most packed together, so find each on its own and combine them
+    let spread = |time: usize, coordinate: fn(Pos) -> isize| {
+        let values: Vec<isize> = robots.iter().map(|robot| coordinate(move_robot(robot, &floor, time))).collect();
+        let (sum, squares): (isize, isize) = values.iter().fold((0, 0), |(sum, squares), &v| (sum + v, squares + v * v));
+        // Variance times the squared number of robots
+        values.len() as isize * squares - sum * sum
+    };
+    let best_col = (0..floor.width()).min_by_key(|&time| spread(time, |pos| pos.col))?;
+    let best_row = (0..floor.height()).min_by_key(|&time| spread(time, |pos| pos.row))?;
+
+    crt(&[(best_col as i64, floor.width() as i64), (best_row as i64, floor.height() as i64)])
+        .map(|(time, _)| time as u32)
 }
 
-fn display_grid(positions: Vec<Pos>, width: usize, height: usize) {
-    let mut grid = vec![vec!['.'; width]; height];
+pub fn part_two(input: &str) -> Option<u32> {
+    part_two_with_params(input, 101, 103)
+}
 
-    for pos in positions {
-        if pos.x < width as isize && pos.y < height as isize {
-            grid[pos.y as usize][pos.x as usize] = 'X';
-        }
-    }
+fn display_grid(positions: Vec<Pos>, width: usize, height: usize) -> String {
+    Render::chars(&Grid::new(width, height, '.')).points(positions, 'X', None).to_string()
+}
 
-    for row in grid {
-        for cell in row {
-            print!("{}", cell);
-        }
-        println!();
-    }
+// Every second of the robots' walk, until they draw the Christmas tree
+fn robot_frames(input: &str) -> Recorder {
+    let floor = TorusGrid::new(101, 103, ());
+    let mut recorder = Recorder::new()
+        .with_images(ImageExport::new(Palette::default().with('X', [40, 200, 70])).scale(3));
+
+    let walk = |robots: &mut Vec<(Pos, Pos)>| {
+        robots.iter_mut().for_each(|robot| robot.0 = move_robot(robot, &floor, 1));
+    };
+    let draw = |robots: &Vec<(Pos, Pos)>| {
+        display_grid(robots.iter().map(|robot| robot.0).collect(), floor.width(), floor.height())
+    };
+    Simulation::in_place(parse(input), walk)
+        .recording(&mut recorder, draw)
+        .run(part_two(input).unwrap_or(0) as usize);
+
+    recorder
 }
 
 
@@ -139,7 +130,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one_with_params(
-            &advent_of_code::template::read_file("examples", DAY),
+            &advent_of_code::template::read_file("examples", DAY).unwrap(),
             11,
             7,
             100
@@ -149,7 +140,10 @@ mod tests {
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(0));
+        // Robots gathering in a 3x3 block at 47 seconds: columns are packed every 11 seconds
+        // from 3, rows every 7 from 5, and the first time both are is 47
+        let result = part_two_with_params(&advent_of_code::template::read_file_part("examples", DAY, 2).unwrap(), 11, 7);
+        assert_eq!(crt(&[(3, 11), (5, 7)]), Some((47, 77)));
+        assert_eq!(result, Some(47));
     }
 }
@@ -1,12 +1,14 @@
-#[path = "utils/grid_utils.rs"] mod utils;
-use crate::utils::{get_value_from_grid_pos, Direction, Pos};
+use advent_of_code::utils::grid_utils::{get_value_from_grid_pos, Direction, Pos};
+use advent_of_code::utils::image::{ImageExport, Palette};
+use advent_of_code::utils::recorder::Recorder;
+use advent_of_code::utils::render::Render;
 
-advent_of_code::solution!(15);
+advent_of_code::solution!(15, record = warehouse_frames);
 
 fn parse(input: &str, is_part_two: bool) -> (Vec<Vec<char>>, Vec<Direction>, Pos) {
     let (grid, movements) = input.split_once("\n\n").unwrap();
     let mut matrix: Vec<Vec<char>>;
-    let mut start_pos: Pos = Pos { x: 0, y: 0 };
+    let mut start_pos: Pos = Pos::new(0, 0);
 
     if !is_part_two {
         matrix = grid.lines().enumerate().map(
@@ -37,15 +39,7 @@ fn parse(input: &str, is_part_two: bool) -> (Vec<Vec<char>>, Vec<Direction>, Pos
         ).collect();
     }
 
-    let moves: Vec<Direction> = movements.chars().filter_map(|c| {
-        match c {
-            '>' => Some(Direction::East),
-            'v' => Some(Direction::South),
-            '<' => Some(Direction::West),
-            '^' => Some(Direction::North),
-            _ => None,
-        }
-    }).collect();
+    let moves: Vec<Direction> = movements.chars().filter_map(Direction::from_char).collect();
 
     (matrix, moves, start_pos)
 }
@@ -79,22 +73,22 @@ pub fn part_one(input: &str) -> Option<usize> {
     for direction in moves {
         let neighbor = current_pos.neighbor(direction);
 
-        match matrix[neighbor.x as usize][neighbor.y as usize] {
+        match matrix[neighbor.row as usize][neighbor.col as usize] {
             '.' => current_pos = neighbor,
             'O' => {
                 let mut next_cell = neighbor.neighbor(direction);
 
                 // Traverse all boxes in the same direction
-                while matrix[next_cell.x as usize][next_cell.y as usize] == 'O' {
+                while matrix[next_cell.row as usize][next_cell.col as usize] == 'O' {
                     next_cell = next_cell.neighbor(direction);
                 }
 
                 // Move 'everything' if there is some available space
-                if matrix[next_cell.x as usize][next_cell.y as usize] == '.' {
+                if matrix[next_cell.row as usize][next_cell.col as usize] == '.' {
                     // In reality, we replace the neighbor pos by '.' (previous first box)
-                    matrix[neighbor.x as usize][neighbor.y as usize] = '.';
+                    matrix[neighbor.row as usize][neighbor.col as usize] = '.';
                     // And push a new box in next_cell
-                    matrix[next_cell.x as usize][next_cell.y as usize] = 'O';
+                    matrix[next_cell.row as usize][next_cell.col as usize] = 'O';
                     current_pos = neighbor;
                 }
             },
@@ -106,17 +100,19 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(calculate_coordinates_sum(&matrix))
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+// Moves the robot around the wide warehouse, calling `on_move` with the grid before the first move and after each one
+fn move_in_wide_warehouse(input: &str, mut on_move: impl FnMut(usize, &Vec<Vec<char>>)) -> Vec<Vec<char>> {
     let (mut matrix, moves, start) = parse(input, true);
+    on_move(0, &matrix);
 
     let mut current_pos = start.clone();
-    for direction in moves {
+    for (step, direction) in moves.into_iter().enumerate() {
         let neighbor = current_pos.neighbor(direction);
 
         match get_value_from_grid_pos(&matrix, neighbor) {
             Some('.') => {
-                matrix[current_pos.x as usize][current_pos.y as usize] = '.';
-                matrix[neighbor.x as usize][neighbor.y as usize] = '@';
+                matrix[current_pos.row as usize][current_pos.col as usize] = '.';
+                matrix[neighbor.row as usize][neighbor.col as usize] = '@';
                 current_pos = neighbor;
             },
             Some(val @ '[') | Some(val @ ']') => {
@@ -139,14 +135,14 @@ pub fn part_two(input: &str) -> Option<usize> {
                         let mut next_cell = neighbor.neighbor(direction).neighbor(direction);
 
                         // Search for the complete list of boxes in the same direction
-                        while matrix[next_cell.x as usize][next_cell.y as usize] == '[' ||
-                            matrix[next_cell.x as usize][next_cell.y as usize] == ']' {
+                        while matrix[next_cell.row as usize][next_cell.col as usize] == '[' ||
+                            matrix[next_cell.row as usize][next_cell.col as usize] == ']' {
                             boxes.push(next_cell);
                             next_cell = next_cell.neighbor(direction);
                         }
 
                         // Move 'everything' if there is some available space
-                        if matrix[next_cell.x as usize][next_cell.y as usize] != '.' {
+                        if matrix[next_cell.row as usize][next_cell.col as usize] != '.' {
                             blocked = true
                         }
                     },
@@ -160,7 +156,7 @@ pub fn part_two(input: &str) -> Option<usize> {
                             for b in current {
                                 let path = b.neighbor(direction);
 
-                                match matrix[path.x as usize][path.y as usize] {
+                                match matrix[path.row as usize][path.col as usize] {
                                     '#' => {
                                         // If we found a wall above/below the box we're searching, that
                                         // means we're blocked, and we can stop the search here.
@@ -200,12 +196,12 @@ pub fn part_two(input: &str) -> Option<usize> {
                     // Impossible to optimize like in part one, every box needs to be moved here
                     for b in boxes.iter().rev() {
                         let n = b.neighbor(direction);
-                        matrix[n.x as usize][n.y as usize] = matrix[b.x as usize][b.y as usize];
-                        matrix[b.x as usize][b.y as usize] = '.';
+                        matrix[n.row as usize][n.col as usize] = matrix[b.row as usize][b.col as usize];
+                        matrix[b.row as usize][b.col as usize] = '.';
                     }
 
-                    matrix[current_pos.x as usize][current_pos.y as usize] = '.';
-                    matrix[neighbor.x as usize][neighbor.y as usize] = '@';
+                    matrix[current_pos.row as usize][current_pos.col as usize] = '.';
+                    matrix[neighbor.row as usize][neighbor.col as usize] = '@';
 
                     current_pos = neighbor
                 }
@@ -214,23 +210,45 @@ pub fn part_two(input: &str) -> Option<usize> {
             // Do nothing otherwise, it is a wall
             _ => {}
         }
+
+        on_move(step + 1, &matrix);
     }
+
+    matrix
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let matrix = move_in_wide_warehouse(input, |_, _| {});
+
     Some(calculate_coordinates_sum(&matrix))
 }
 
+fn warehouse_frames(input: &str) -> Recorder {
+    let palette = Palette::default()
+        .with('#', [120, 120, 140])
+        .with('[', [200, 140, 60])
+        .with(']', [200, 140, 60])
+        .with('@', [230, 50, 50]);
+    let mut recorder = Recorder::new().with_images(ImageExport::new(palette).scale(8));
+
+    move_in_wide_warehouse(input, |step, matrix| recorder.record(step, Render::chars(matrix)));
+
+    recorder
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(2028));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, Some(9021));
     }
 }
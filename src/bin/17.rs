@@ -1,195 +1,48 @@
-use std::collections::HashMap;
-use std::ops::Div;
+use advent_of_code::template::Parsed;
+use advent_of_code::utils::vm::{disassembly, Vm};
 
-advent_of_code::solution!(17);
+advent_of_code::solution!(17, trace = program_trace);
 
-#[derive(Debug, Clone)]
-struct Computer {
-    registers: HashMap<String, i64>,
-    operations: Vec<i64>,
-    outputs: Vec<i64>,
-    pointer: usize,
-}
+// Instructions to trace at most, the programs looping once per output value
+const TRACE_LIMIT: usize = 10_000;
 
-fn process_operation(computer: &mut Computer, opcode_pos: usize) -> Option<usize> {
-    let (opcode, literal_val) = (
-        computer.operations[opcode_pos],
-        computer.operations[opcode_pos + 1]
-    );
-
-    let mut combo_val: i64 = 0;
-    if literal_val <= 3 {
-        // Litteral value
-        combo_val = literal_val;
-    } else if literal_val == 4 {
-        // Register A
-        combo_val = *computer.registers.get("A").unwrap();
-    } else if literal_val == 5 {
-        // Register B
-        combo_val = *computer.registers.get("B").unwrap();
-    } else if literal_val == 6 {
-        // Register C
-        combo_val = *computer.registers.get("C").unwrap();
-    } else {
-        // Nothing **FOR NOW**
-    }
+// The disassembled program, then every instruction executed by part one with the registers after it
+fn program_trace(input: &str) -> String {
+    let mut vm: Vm = input.parse().unwrap_or_else(|e| panic!("Invalid input, {e}"));
+    let mut text = disassembly(vm.program());
+    text.push_str("\n\n");
 
-    let (a, b, c) = (
-        *computer.registers.get("A").unwrap(),
-        *computer.registers.get("B").unwrap(),
-        *computer.registers.get("C").unwrap()
-    );
-
-    match opcode {
-        0 => {
-            computer.registers.insert("A".to_string(), a / 2_i32.pow(combo_val as u32) as i64);
-        },
-        1 => {
-            computer.registers.insert("B".to_string(), b ^ literal_val);
-        },
-        2 => {
-            computer.registers.insert("B".to_string(), combo_val % 8);
-        },
-        3 => {
-            if a > 0 {
-                return Some(literal_val as usize);
-            }
-        },
-        4 => {
-            computer.registers.insert("B".to_string(), b ^ c);
-        },
-        5 => {
-            computer.outputs.push(combo_val % 8)
-        },
-        6 => {
-            computer.registers.insert("B".to_string(), a / 2_i32.pow(combo_val as u32) as i64);
-        },
-        7 => {
-            computer.registers.insert("C".to_string(), a / 2_i32.pow(combo_val as u32) as i64);
-        },
-        _ => unimplemented!()
+    match vm.trace(TRACE_LIMIT) {
+        Ok(trace) => trace.iter().for_each(|entry| text.push_str(&format!("{entry}\n"))),
+        Err(e) => text.push_str(&format!("Invalid program, {e}\n")),
     }
-
-    None
+    text
 }
 
-fn parse_input(input: &str) -> Computer {
-    let mut registers = HashMap::new();
-    let mut operations = Vec::new();
-
-    let parts: Vec<&str> = input.split("\n\n").collect();
-
-    // Parse registers
-    for line in parts[0].lines() {
-        if let Some((key, value)) = line.split_once(": ") {
-            registers.insert(key.trim().replace("Register ", "").to_string(), value.trim().parse().unwrap());
-        }
-    }
-
-    // Parse program
-    if let Some(prog_str) = parts.get(1) {
-        let numbers: Vec<i64> = prog_str.trim().split(&[',', ' '][..])
-            .filter_map(|s| s.trim().parse().ok())
-            .collect();
-        operations.extend(numbers);
-    }
+pub fn part_one(Parsed(mut vm): Parsed<Vm>) -> Option<String> {
+    vm.run().unwrap_or_else(|e| panic!("Invalid program, {e}"));
 
-    Computer { registers, operations, outputs: Vec::new(), pointer: 0 }
+    Some(vm.output_string())
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let mut computer = parse_input(input);
-
-    let mut current_pointer: usize = 0;
-    loop {
-        if computer.operations.len() <= current_pointer {
-            break;
-        }
-
-        let jump = process_operation(&mut computer, current_pointer);
-        if jump.is_some() {
-            current_pointer = jump.unwrap();
-        } else {
-            current_pointer += 2;
-        }
-    }
-
-    Some(computer.outputs.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(","))
-}
-
-struct Computer2<'a> {
-    program: &'a [u64],
-    ip: usize,
-    a: u64,
-    b: u64,
-    c: u64,
-}
-
-impl Computer2<'_> {
-    fn run(&mut self) -> Option<u64> {
-        while self.ip < self.program.len() {
-            let combo = |index| match self.program[index] {
-                0..4 => self.program[index],
-                4 => self.a,
-                5 => self.b,
-                6 => self.c,
-                _ => unreachable!(),
-            };
-
-            match self.program[self.ip] {
-                0 => self.a >>= combo(self.ip + 1),
-                1 => self.b ^= self.program[self.ip + 1],
-                2 => self.b = combo(self.ip + 1) % 8,
-                3 => {
-                    if self.a != 0 {
-                        self.ip = self.program[self.ip + 1] as usize;
-                        continue;
-                    }
-                }
-                4 => self.b ^= self.c,
-                5 => {
-                    let out = combo(self.ip + 1) % 8;
-                    self.ip += 2;
-                    return Some(out);
-                }
-                6 => self.b = self.a >> combo(self.ip + 1),
-                7 => self.c = self.a >> combo(self.ip + 1),
-                _ => unreachable!(),
-            }
-
-            self.ip += 2;
-        }
-
-        None
-    }
-}
-
-
-pub fn part_two(input: &str) -> Option<u64> {
-    let cmpt = parse_input(input);
-    let input_numbers: Vec<u64> = cmpt.operations.iter().map(|o| *o as u64).collect();
-    let operations = input_numbers.clone();
+pub fn part_two(Parsed(mut vm): Parsed<Vm>) -> Option<u64> {
+    let program = vm.program().to_vec();
 
+    // The program outputs one value per 3 bits of A, shifting A right by 3 each loop: build A
+    // from its highest bits, keeping the candidates whose first output matches
     let mut valid = vec![0];
 
-    for &out in input_numbers.iter().rev() {
+    for &out in program.iter().rev() {
         let mut next = Vec::new();
 
         for v in valid {
             for n in 0..8 {
                 let a = (v << 3) | n;
-                let mut computer =
-                    Computer2 {
-                        program: &operations, ip: 0,
-                        a,
-                        b: *cmpt.registers.get("B").unwrap() as u64,
-                        c: *cmpt.registers.get("C").unwrap() as u64,
-                    };
-
-                if let Some(result) = computer.run() {
-                    if result == out {
-                        next.push(a);
-                    }
+                vm.reset(a);
+
+                let first = vm.next_output().unwrap_or_else(|e| panic!("Invalid program, {e}"));
+                if first == Some(out) {
+                    next.push(a);
                 }
             }
         }
@@ -197,7 +50,7 @@ pub fn part_two(input: &str) -> Option<u64> {
         valid = next;
     }
 
-    Some(*valid.iter().min().unwrap())
+    valid.into_iter().min()
 }
 
 #[cfg(test)]
@@ -206,13 +59,21 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let input = advent_of_code::template::read_file("examples", DAY).unwrap();
+        let result = part_one(Parsed(input.parse().unwrap()));
+        assert_eq!(result, Some("5,7,5,0,3,4,1,1,6,5,5,3,0".to_string()));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let input = advent_of_code::template::read_file("examples", DAY).unwrap();
+        let mut vm: Vm = input.parse().unwrap();
+        let result = part_two(Parsed(vm.clone()));
+        assert_eq!(result, Some(109020013201563));
+
+        // The program outputs itself
+        let program = vm.program().to_vec();
+        vm.reset(result.unwrap());
+        assert_eq!(vm.run().unwrap(), program);
     }
 }
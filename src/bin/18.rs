@@ -1,19 +1,9 @@
-#[path = "utils/grid_utils.rs"] mod utils;
-
-use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
-use crate::utils::{get_value_from_grid, Direction, Pos};
+use advent_of_code::utils::grid::GridLike;
+use advent_of_code::utils::grid_utils::{get_value_from_grid_pos, Maze, Pos, CARDINAL_DIRECTIONS};
+use advent_of_code::utils::union_find::GridUnionFind;
 
 advent_of_code::solution!(18);
 
-#[derive(Clone, Eq, PartialEq, Debug)]
-struct State {
-    cost: usize,
-    position: Pos,
-    prev_direction: Option<Direction>,
-    path: Vec<Pos>,
-}
-
-
 fn parse(input: &str, size: usize, bytes: usize) -> (Vec<Pos>, Vec<Vec<char>>) {
     let mut count = 0;
     let mut grid = vec![vec!['.'; size + 1]; size + 1];
@@ -27,59 +17,24 @@ fn parse(input: &str, size: usize, bytes: usize) -> (Vec<Pos>, Vec<Vec<char>>) {
                 count += 1;
                 return None;
             }
-            Some(Pos::new(x, y))
+            Some(Pos::from_xy(x, y))
         }
     ).collect();
 
     (positions_left, grid)
 }
 
-fn bfs(start: Pos, goal: Pos, grid: &Vec<Vec<char>>) -> Option<Vec<Pos>> {
-    let mut queue = VecDeque::new();
-    let mut visited = vec![vec![false; grid[0].len()]; grid.len()];
-    let mut parent = vec![vec![None; grid[0].len()]; grid.len()];
-
-    queue.push_back(start);
-    visited[start.y as usize][start.x as usize] = true;
-
-    while let Some(current) = queue.pop_front() {
-        if current == goal {
-            let mut path = Vec::new();
-            let mut pos = Some(current);
-            while let Some(p) = pos {
-                path.push(p);
-                pos = parent[p.y as usize][p.x as usize];
-            }
-            path.reverse();
-            return Some(path);
-        }
-
-        for neighbor in current.neighbors(&[Direction::East, Direction::South, Direction::West, Direction::North]) {
-            let val = get_value_from_grid(&grid, neighbor.x as usize, neighbor.y as usize);
-            if let Some(cell) = val {
-                if !visited[neighbor.y as usize][neighbor.x as usize] && *cell != '#' {
-                    visited[neighbor.y as usize][neighbor.x as usize] = true;
-                    parent[neighbor.y as usize][neighbor.x as usize] = Some(current);
-                    queue.push_back(neighbor);
-                }
-            }
-        }
-    }
-
-    None
+fn build_maze(grid: Vec<Vec<char>>, size: usize) -> Maze {
+    Maze::builder()
+        .start(Pos::new(0, 0))
+        .end(Pos::new(size as isize, size as isize))
+        .build(grid)
 }
 
 pub fn part_one_with_params(input: &str, size: usize, bytes: usize) -> Option<usize> {
-    let (positions_left, grid) = parse(input, size, bytes);
-
-    let start_pos = Pos::new(0, 0);
-    let end_pos = Pos::new(size as isize, size as isize);
+    let (_, grid) = parse(input, size, bytes);
 
-    let result = bfs(start_pos, end_pos, &grid);
-    if result.is_some() {
-        return Some(result.unwrap().len() - 1)
-    }
-    None
+    build_maze(grid, size).shortest_path_cost()
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
@@ -88,15 +43,43 @@ pub fn part_one(input: &str) -> Option<usize> {
 
 fn part_two_with_params(input: &str, size: usize, bytes: usize) -> Option<String> {
     let (positions, mut grid) = parse(input, size, bytes);
-    let start_pos = Pos::new(0, 0);
-    let end_pos = Pos::new(size as isize, size as isize);
+    for pos in &positions {
+        grid[pos.row as usize][pos.col as usize] = '#';
+    }
+
+    // Once every byte has fallen, take them back in reverse order until the exit is reachable
+    // again: the last byte removed is the first one cutting the path
+    let mut sets = GridUnionFind::for_grid(&grid);
+    let open = |grid: &Vec<Vec<char>>, pos: Pos| get_value_from_grid_pos(grid, pos) == Some(&'.');
+    let connect = |sets: &mut GridUnionFind, grid: &Vec<Vec<char>>, pos: Pos| {
+        for neighbor in grid.neighbors(pos, &CARDINAL_DIRECTIONS) {
+            if open(grid, neighbor) {
+                sets.union(pos, neighbor);
+            }
+        }
+    };
+
+    for row in 0..=size {
+        for col in 0..=size {
+            let pos = Pos::new(row as isize, col as isize);
+            if open(&grid, pos) {
+                connect(&mut sets, &grid, pos);
+            }
+        }
+    }
+
+    // Removing bytes only connects more cells: if no byte cuts the path, none is the answer
+    let (start, end) = (Pos::new(0, 0), Pos::new(size as isize, size as isize));
+    if sets.connected(start, end) {
+        return None;
+    }
 
-    for pos in positions {
-        grid[pos.y as usize][pos.x as usize] = '#';
+    for &pos in positions.iter().rev() {
+        grid[pos.row as usize][pos.col as usize] = '.';
+        connect(&mut sets, &grid, pos);
 
-        let result = bfs(start_pos, end_pos, &grid);
-        if result.is_none() {
-            return Some(format!("{},{}", pos.x, pos.y))
+        if sets.connected(start, end) {
+            return Some(format!("{},{}", pos.col, pos.row))
         }
     }
 
@@ -114,13 +97,19 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one_with_params(&advent_of_code::template::read_file("examples", DAY), 6, 12);
+        let result = part_one_with_params(&advent_of_code::template::read_file("examples", DAY).unwrap(), 6, 12);
         assert_eq!(result, Some(22));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two_with_params(&advent_of_code::template::read_file("examples", DAY), 6, 20);
+        let result = part_two_with_params(&advent_of_code::template::read_file("examples", DAY).unwrap(), 6, 20);
         assert_eq!(result, Some("6,1".to_string()));
     }
+
+    #[test]
+    fn test_part_two_never_cut() {
+        let result = part_two_with_params("1,0\n1,1\n2,1\n", 2, 0);
+        assert_eq!(result, None);
+    }
 }
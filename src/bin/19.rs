@@ -1,61 +1,59 @@
 use std::collections::{HashSet};
+use std::error::Error;
+use advent_of_code::template::PuzzleInput;
+use advent_of_code::utils::memo::Memoized;
+use advent_of_code::utils::parse::{comma_list, sections_array};
 
 advent_of_code::solution!(19);
 
-fn parse_input(input: &str) -> (HashSet<String>, Vec<String>) {
-    let mut sections = input.split("\n\n");
+#[derive(Clone)]
+pub struct Towels {
+    patterns: HashSet<String>,
+    designs: Vec<String>,
+}
 
-    let towel_patterns: HashSet<String> = sections
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .collect();
-    let designs: Vec<String> = sections
-        .next()
-        .unwrap()
-        .lines()
-        .map(|s| s.trim().to_string())
-        .collect();
+impl PuzzleInput<'_> for Towels {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let [towel_patterns, designs] = sections_array(input)?;
 
-    (towel_patterns, designs)
-}
+        let patterns: HashSet<String> = towel_patterns.parse(comma_list::<String>)?.into_iter().collect();
+        let designs: Vec<String> = designs.text
+            .lines()
+            .map(|s| s.trim().to_string())
+            .collect();
 
-fn count_ways(patterns: &HashSet<String>, design: &str) -> usize {
-    let design_length = design.len();
-    let mut dp = vec![0; design_length + 1];
-    // Default case (empty)
-    dp[0] = 1;
+        Ok(Towels { patterns, designs })
+    }
+}
 
-    for i in 1..=design_length {
-        for pattern in patterns {
-            let pattern_len = pattern.len();
-            if i >= pattern_len && &design[i - pattern_len..i] == pattern {
-                dp[i] += dp[i - pattern_len];
-            }
+// Ways to make each design, the same design endings coming up again and again across designs
+fn count_ways<'a>(patterns: &HashSet<String>, designs: &'a [String]) -> Vec<usize> {
+    let mut ways = Memoized::new(|ways: &mut dyn FnMut(&'a str) -> usize, design: &'a str| {
+        // Default case (empty)
+        if design.is_empty() {
+            return 1;
         }
-    }
 
-    dp[design_length]
-}
+        patterns
+            .iter()
+            .filter_map(|pattern| design.strip_prefix(pattern.as_str()))
+            .map(ways)
+            .sum()
+    });
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let (patterns, designs) = parse_input(input);
+    designs.iter().map(|design| ways.call(design)).collect()
+}
 
-    Some(designs
-        .iter()
-        .filter(|design| count_ways(&patterns, design) > 0)
+pub fn part_one(Towels { patterns, designs }: Towels) -> Option<usize> {
+    Some(count_ways(&patterns, &designs)
+        .into_iter()
+        .filter(|&ways| ways > 0)
         .count())
 }
 
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let (patterns, designs) = parse_input(input);
-
-    Some(designs
-        .iter()
-        .map(|design| count_ways(&patterns, design))
-        .sum())
+pub fn part_two(Towels { patterns, designs }: Towels) -> Option<usize> {
+    Some(count_ways(&patterns, &designs).into_iter().sum())
 }
 
 #[cfg(test)]
@@ -64,13 +62,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(Towels::parse(&advent_of_code::template::read_file("examples", DAY).unwrap()).unwrap());
         assert_eq!(result, Some(6));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(Towels::parse(&advent_of_code::template::read_file("examples", DAY).unwrap()).unwrap());
         assert_eq!(result, Some(16));
     }
 }
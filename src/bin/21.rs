@@ -1,5 +1,6 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 use std::hash::Hash;
+use advent_of_code::utils::memo::Memo;
 
 advent_of_code::solution!(21);
 
@@ -105,34 +106,30 @@ fn find_shortest_sequence(
     cursors: &mut Vec<u8>,
     numeric: &[[u8; 3]],
     diagonal: &[[u8; 3]],
-    cache: &mut HashMap<(Vec<u8>, usize, u8), usize>,
+    cache: &mut Memo<(Vec<u8>, usize, u8), usize>,
 ) -> usize {
     let cache_key = (s.to_vec(), depth, cursors[depth]);
-    if let Some(cached) = cache.get(&cache_key) {
-        return *cached;
-    }
-
-    let mut result = 0;
-    for &c in s {
-        let paths =
-            find_shortest_paths(if highest { numeric } else { diagonal }, cursors[depth], c);
-        if depth == 0 {
-            result += paths.into_iter().map(|l| l.len()).min().unwrap();
-        } else {
-            result += paths
-                .into_iter()
-                .map(|p| {
-                    find_shortest_sequence(&p, depth - 1, false, cursors, numeric, diagonal, cache)
-                })
-                .min()
-                .unwrap();
+    cache.get(cache_key, |cache| {
+        let mut result = 0;
+        for &c in s {
+            let paths =
+                find_shortest_paths(if highest { numeric } else { diagonal }, cursors[depth], c);
+            if depth == 0 {
+                result += paths.into_iter().map(|l| l.len()).min().unwrap();
+            } else {
+                result += paths
+                    .into_iter()
+                    .map(|p| {
+                        find_shortest_sequence(&p, depth - 1, false, cursors, numeric, diagonal, cache)
+                    })
+                    .min()
+                    .unwrap();
+            }
+            cursors[depth] = c;
         }
-        cursors[depth] = c;
-    }
-
-    cache.insert(cache_key, result);
 
-    result
+        result
+    })
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
@@ -147,7 +144,7 @@ pub fn part_one(input: &str) -> Option<usize> {
         [b'<', b'v', b'>']
     ];
 
-    let mut cache = HashMap::new();
+    let mut cache = Memo::new();
     let lines = input.lines().collect::<Vec<_>>();
 
     let mut total = 0;
@@ -183,7 +180,7 @@ pub fn part_two(input: &str) -> Option<usize> {
         [b'<', b'v', b'>']
     ];
 
-    let mut cache = HashMap::new();
+    let mut cache = Memo::new();
     let lines = input.lines().collect::<Vec<_>>();
 
     let mut total = 0;
@@ -213,13 +210,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, None);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY).unwrap());
         assert_eq!(result, None);
     }
 }
@@ -7,6 +7,7 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
+    use advent_of_code::template::commands::solve;
     use advent_of_code::template::Day;
     use std::process;
 
@@ -27,6 +28,7 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            exports: solve::Exports,
         },
         All {
             release: bool,
@@ -73,6 +75,15 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                exports: solve::Exports {
+                    dot: args.opt_value_from_str("--dot")?,
+                    play: args.contains("--play"),
+                    fps: args.opt_value_from_str("--fps")?,
+                    frames: args.opt_value_from_str("--frames")?,
+                    record: args.opt_value_from_str("--record")?,
+                    steps: args.opt_value_from_str("--steps")?,
+                    trace: args.opt_value_from_str("--trace")?,
+                },
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -117,7 +128,8 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                exports,
+            } => solve::handle(day, release, dhat, submit, exports),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
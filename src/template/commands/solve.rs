@@ -2,7 +2,46 @@ use std::process::{Command, Stdio};
 
 use crate::template::Day;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// Options forwarded to the solution, for the days opting into them in their `solution!` call.
+#[derive(Debug, Default)]
+pub struct Exports {
+    pub dot: Option<String>,
+    pub play: bool,
+    pub fps: Option<String>,
+    pub frames: Option<String>,
+    pub record: Option<String>,
+    pub steps: Option<String>,
+    pub trace: Option<String>,
+}
+
+impl Exports {
+    fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        let options = [
+            ("--dot", &self.dot),
+            ("--fps", &self.fps),
+            ("--frames", &self.frames),
+            ("--record", &self.record),
+            ("--steps", &self.steps),
+            ("--trace", &self.trace),
+        ];
+        for (flag, value) in options {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value.clone());
+            }
+        }
+
+        if self.play {
+            args.push("--play".to_string());
+        }
+
+        args
+    }
+}
+
+pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>, exports: Exports) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -23,6 +62,8 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    cmd_args.extend(exports.to_args());
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
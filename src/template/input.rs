@@ -0,0 +1,80 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Argument of a solution part, parsed from the puzzle input once before the part is run and timed.
+///
+/// Parts taking `&str` get the raw input, as before. A day can implement this trait for its own
+/// input type, or take any `FromStr` type through `Parsed`.
+pub trait PuzzleInput<'a>: Sized {
+    /// Whether parsing does any work, and so is worth reporting in the runner output.
+    const PARSES: bool = true;
+
+    fn parse(input: &'a str) -> Result<Self, Box<dyn Error>>;
+}
+
+impl<'a> PuzzleInput<'a> for &'a str {
+    const PARSES: bool = false;
+
+    fn parse(input: &'a str) -> Result<Self, Box<dyn Error>> {
+        Ok(input)
+    }
+}
+
+impl PuzzleInput<'_> for String {
+    const PARSES: bool = false;
+
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(input.to_string())
+    }
+}
+
+/// A part argument parsed with `FromStr`, e.g. `pub fn part_one(Parsed(grid): Parsed<Grid>)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed<T>(pub T);
+
+impl<T> PuzzleInput<'_> for Parsed<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        input.parse().map(Parsed).map_err(|e: T::Err| e.to_string().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse::ParseError;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Sum(i64);
+
+    impl FromStr for Sum {
+        type Err = ParseError;
+
+        fn from_str(input: &str) -> Result<Self, Self::Err> {
+            input.lines().enumerate()
+                .map(|(i, line)| line.parse::<i64>().map_err(|e| ParseError::at(line, 0, e.to_string()).shifted(i)))
+                .sum::<Result<i64, _>>()
+                .map(Sum)
+        }
+    }
+
+    #[test]
+    fn test_raw_input() {
+        assert_eq!(<&str as PuzzleInput>::parse("1\n2\n").unwrap(), "1\n2\n");
+        assert_eq!(String::parse("abc").unwrap(), "abc");
+        const { assert!(!<&str as PuzzleInput>::PARSES && !String::PARSES) };
+    }
+
+    #[test]
+    fn test_parsed() {
+        const { assert!(Parsed::<Sum>::PARSES) };
+        assert_eq!(Parsed::<Sum>::parse("1\n2\n3").unwrap(), Parsed(Sum(6)));
+
+        let error = Parsed::<Sum>::parse("1\nx").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 1: invalid digit found in string");
+    }
+}
@@ -1,3 +1,7 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::io;
+use std::path::PathBuf;
 use std::{env, fs};
 
 pub mod aoc_cli;
@@ -5,8 +9,10 @@ pub mod commands;
 pub mod runner;
 
 pub use day::*;
+pub use input::*;
 
 mod day;
+mod input;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
@@ -15,35 +21,103 @@ pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// An input file that cannot be used by a solution.
+#[derive(Debug)]
+pub enum InputError {
+    Unreadable(PathBuf, io::Error),
+    Empty(PathBuf),
+}
+
+impl Error for InputError {}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Unreadable(path, e) => write!(f, "could not open input file {}: {e}", path.display()),
+            InputError::Empty(path) => write!(
+                f,
+                "input file {} is empty, paste the puzzle input in it or run `cargo download <day>`",
+                path.display()
+            ),
+        }
+    }
+}
+
 /// Helper function that reads a text file to a string.
-#[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
+pub fn read_file(folder: &str, day: Day) -> Result<String, InputError> {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    read_input(filepath)
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
-#[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, day: Day, part: u8) -> Result<String, InputError> {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd
         .join("data")
         .join(folder)
         .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    read_input(filepath)
+}
+
+/// Reads an input with `\n` line endings and a trailing newline, as downloaded inputs have.
+fn read_input(path: PathBuf) -> Result<String, InputError> {
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => return Err(InputError::Unreadable(path, e)),
+    };
+    if text.trim().is_empty() {
+        return Err(InputError::Empty(path));
+    }
+
+    // A failed download saves the page explaining why, rather than the input
+    let start = text.trim_start().to_ascii_lowercase();
+    if start.starts_with("<!doctype html") || start.starts_with("<html") || start.starts_with("puzzle inputs differ by user") {
+        eprintln!(
+            "Warning: input file {} looks like an error page, check your session cookie and download it again.",
+            path.display()
+        );
+    }
+
+    let mut text = text.replace("\r\n", "\n");
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    Ok(text)
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Parts take either the raw `&str` input or any type implementing `PuzzleInput`, which is parsed
+/// once before the parts are timed and shared by both of them, so they must take the same type.
+/// A parse error stops the runner with its message.
+///
+/// Alternatively, `dot = <fn(&str) -> String>` opts the day into `cargo solve <day> --dot <file>`,
+/// which writes the returned Graphviz DOT document to the file before running the parts.
+///
+/// Likewise, `record = <fn(&str) -> Recorder>` opts a step simulation into `--play`, which plays
+/// its frames in the terminal, `--frames <file>`, which writes them to a text file, and
+/// `--record <file>`, which encodes them as an animated GIF or a PNG sequence. `--steps` selects
+/// the frames to write.
+///
+/// And `trace = <fn(&str) -> String>` opts the day into `--trace <file>`, which writes the returned
+/// text, e.g. a program's disassembly and execution trace, to the file before running the parts.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
         $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
     };
+    ($day:expr, dot = $dot:expr) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2] @dot $dot;);
+    };
+    ($day:expr, record = $record:expr) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2] @record $record;);
+    };
+    ($day:expr, trace = $trace:expr) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2] @trace $trace;);
+    };
     ($day:expr, 1) => {
         $crate::solution!(@impl $day, [part_one, 1]);
     };
@@ -51,7 +125,7 @@ macro_rules! solution {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )* $(@dot $dot:expr;)? $(@record $record:expr;)? $(@trace $trace:expr;)?) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -61,8 +135,47 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let input = $crate::template::read_file("inputs", DAY).unwrap_or_else(|e| {
+                eprintln!("{e}");
+                std::process::exit(1);
+            });
+            $( export_dot($dot, &input); )?
+            $( replay($record, &input); )?
+            $( export_trace($trace, &input); )?
+            let parsed = parse_input(&input);
+            $( run_part($func, &parsed, DAY, $part); )*
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A file of its own in the temporary directory, removed once read
+    fn read_temp(name: &str, content: Option<&[u8]>) -> Result<String, InputError> {
+        let path = env::temp_dir().join(format!("aoc-read-input-{}-{name}.txt", std::process::id()));
+        if let Some(content) = content {
+            fs::write(&path, content).unwrap();
+        }
+        let result = read_input(path.clone());
+        let _ = fs::remove_file(path);
+        result
+    }
+
+    #[test]
+    fn test_read_input_errors() {
+        assert!(matches!(read_temp("missing", None), Err(InputError::Unreadable(_, _))));
+
+        let empty = read_temp("empty", Some(b" \n\n"));
+        assert!(matches!(empty, Err(InputError::Empty(_))));
+        assert!(empty.unwrap_err().to_string().contains("is empty, paste the puzzle input"));
+    }
+
+    #[test]
+    fn test_read_input_normalization() {
+        assert_eq!(read_temp("crlf", Some(b"1,2\r\n3,4\r\n")).unwrap(), "1,2\n3,4\n");
+        assert_eq!(read_temp("no-newline", Some(b"1,2\n3,4")).unwrap(), "1,2\n3,4\n");
+        assert_eq!(read_temp("unchanged", Some(b"1,2\n")).unwrap(), "1,2\n");
+    }
+}
@@ -4,12 +4,13 @@ use std::hint::black_box;
 use std::io::{stdout, Write};
 use std::process::Output;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, env, fs, process};
 
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, Day, PuzzleInput, ANSI_ITALIC, ANSI_RESET};
+use crate::utils::recorder::{parse_steps, Recorder};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: &I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
     let (result, duration, samples) =
@@ -22,17 +23,36 @@ pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, d
     }
 }
 
+/// Parse the input once for every part, outside of their timing, exiting with the error if it is invalid.
+pub fn parse_input<'a, I: PuzzleInput<'a>>(input: &'a str) -> I {
+    let timer = Instant::now();
+
+    match I::parse(input) {
+        Ok(parsed) => {
+            if I::PARSES {
+                println!("Input parsed ({:.1?})", timer.elapsed());
+            }
+            parsed
+        }
+        Err(e) => {
+            eprintln!("✖ could not parse the input: {e}");
+            process::exit(1);
+        }
+    }
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
-    input: I,
+    input: &I,
     hook: impl Fn(&T),
 ) -> (T, Duration, u128) {
+    let cloned = input.clone();
     let timer = Instant::now();
     let result = {
-        let input = input.clone();
+        let input = cloned;
 
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
@@ -52,7 +72,7 @@ fn run_timed<I: Clone, T>(
     (result, run.0, run.1)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: &I, base_time: &Duration) -> (Duration, u128) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
@@ -129,6 +149,82 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
+/// Write the DOT document built by `func` to the file passed with `--dot`, if any.
+pub fn export_dot(func: impl Fn(&str) -> String, input: &str) {
+    let args: Vec<String> = env::args().collect();
+
+    let Some(path) = args.iter().position(|x| x == "--dot").and_then(|i| args.get(i + 1)) else {
+        return;
+    };
+
+    match fs::write(path, func(input)) {
+        Ok(()) => println!("Wrote DOT graph to \"{path}\"."),
+        Err(e) => eprintln!("Failed to write DOT graph to \"{path}\": {e}"),
+    }
+}
+
+/// Write the execution trace built by `func` to the file passed with `--trace`, if any.
+pub fn export_trace(func: impl Fn(&str) -> String, input: &str) {
+    let args: Vec<String> = env::args().collect();
+
+    let Some(path) = args.iter().position(|x| x == "--trace").and_then(|i| args.get(i + 1)) else {
+        return;
+    };
+
+    match fs::write(path, func(input)) {
+        Ok(()) => println!("Wrote trace to \"{path}\"."),
+        Err(e) => eprintln!("Failed to write trace to \"{path}\": {e}"),
+    }
+}
+
+/// Play the frames recorded by `func` with `--play`, at the speed passed with `--fps`, write them
+/// to the text file passed with `--frames`, or encode them to the GIF or PNG files passed with `--record`.
+pub fn replay(func: impl Fn(&str) -> Recorder, input: &str) {
+    let args: Vec<String> = env::args().collect();
+    let value_of = |flag: &str| args.iter().position(|x| x == flag).and_then(|i| args.get(i + 1));
+
+    let play = args.iter().any(|x| x == "--play");
+    let frames_path = value_of("--frames");
+    let record_path = value_of("--record");
+    if !play && frames_path.is_none() && record_path.is_none() {
+        return;
+    }
+
+    let recorder = func(input);
+    let steps = match value_of("--steps") {
+        Some(steps) => parse_steps(steps).unwrap_or_else(|| {
+            eprintln!("Invalid steps \"{steps}\". Format: 10, 10..20, 10..=20, 10.. or ..20");
+            process::exit(1);
+        }),
+        None => parse_steps("..").unwrap(),
+    };
+
+    if let Some(path) = frames_path {
+        match recorder.dump(path, steps) {
+            Ok(()) => println!("Wrote {} frames to \"{path}\".", recorder.select(steps).count()),
+            Err(e) => eprintln!("Failed to write frames to \"{path}\": {e}"),
+        }
+    }
+
+    if let Some(path) = record_path {
+        match recorder.export_images(path, steps) {
+            Ok(count) => println!("Recorded {count} frames to \"{path}\"."),
+            Err(e) => eprintln!("Failed to record frames to \"{path}\": {e}"),
+        }
+    }
+
+    if play {
+        let fps = match value_of("--fps") {
+            Some(fps) => fps.parse::<f64>().ok().filter(|fps| *fps > 0.0).unwrap_or_else(|| {
+                eprintln!("Invalid frame rate \"{fps}\", expected a positive number of frames per second.");
+                process::exit(1);
+            }),
+            None => 10.0,
+        };
+        recorder.play(fps);
+    }
+}
+
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
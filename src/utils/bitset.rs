@@ -0,0 +1,233 @@
+use crate::utils::grid_utils::{Direction, Pos};
+use crate::utils::search::VisitedSet;
+
+/// Fixed size set of bits, the storage behind the grid sets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Bits {
+    words: Vec<u64>,
+}
+
+impl Bits {
+    fn new(size: usize) -> Self {
+        Bits { words: vec![0; size.div_ceil(64)] }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    // Returns whether the bit changed
+    fn set(&mut self, index: usize, value: bool) -> bool {
+        let word = &mut self.words[index / 64];
+        let mask = 1 << (index % 64);
+        let changed = (*word & mask != 0) != value;
+        if value { *word |= mask } else { *word &= !mask }
+        changed
+    }
+
+    fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            (0..64).filter(move |bit| word & (1 << bit) != 0).map(move |bit| i * 64 + bit)
+        })
+    }
+
+    fn combine(&mut self, other: &Bits, op: impl Fn(u64, u64) -> u64) {
+        assert_eq!(self.words.len(), other.words.len(), "both sets must have the same bounds");
+        self.words.iter_mut().zip(&other.words).for_each(|(word, &other)| *word = op(*word, other));
+    }
+}
+
+/// A set of positions of a `width` x `height` grid, storing one bit per cell.
+///
+/// Positions outside of the bounds are never contained, and inserting one panics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridBitSet {
+    bits: Bits,
+    width: usize,
+    height: usize,
+}
+
+impl GridBitSet {
+    pub fn new(width: usize, height: usize) -> Self {
+        GridBitSet { bits: Bits::new(width * height), width, height }
+    }
+
+    /// An empty set with the bounds of a `Vec<Vec<_>>` grid.
+    pub fn for_grid<T>(grid: &[Vec<T>]) -> Self {
+        GridBitSet::new(grid.first().map_or(0, |row| row.len()), grid.len())
+    }
+
+    fn index_of(&self, pos: &Pos) -> Option<usize> {
+        let row = usize::try_from(pos.row).ok().filter(|&row| row < self.height)?;
+        let col = usize::try_from(pos.col).ok().filter(|&col| col < self.width)?;
+        Some(row * self.width + col)
+    }
+
+    fn pos_of(&self, index: usize) -> Pos {
+        Pos::new((index / self.width) as isize, (index % self.width) as isize)
+    }
+
+    /// Returns whether the position was not in the set yet, like `HashSet::insert`.
+    pub fn insert(&mut self, pos: Pos) -> bool {
+        let index = self.index_of(&pos).unwrap_or_else(|| panic!("{pos:?} is outside of the set bounds"));
+        self.bits.set(index, true)
+    }
+
+    pub fn remove(&mut self, pos: &Pos) -> bool {
+        self.index_of(pos).is_some_and(|index| self.bits.set(index, false))
+    }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        self.index_of(pos).is_some_and(|index| self.bits.get(index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.words.fill(0);
+    }
+
+    /// Positions in the set, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.bits.ones().map(|index| self.pos_of(index))
+    }
+
+    pub fn union_with(&mut self, other: &GridBitSet) {
+        self.bits.combine(&other.bits, |a, b| a | b);
+    }
+
+    pub fn intersect_with(&mut self, other: &GridBitSet) {
+        self.bits.combine(&other.bits, |a, b| a & b);
+    }
+
+    pub fn difference_with(&mut self, other: &GridBitSet) {
+        self.bits.combine(&other.bits, |a, b| a & !b);
+    }
+
+    pub fn is_subset(&self, other: &GridBitSet) -> bool {
+        self.bits.words.iter().zip(&other.bits.words).all(|(a, b)| a & !b == 0)
+    }
+}
+
+impl Extend<Pos> for GridBitSet {
+    fn extend<I: IntoIterator<Item = Pos>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|pos| { self.insert(pos); });
+    }
+}
+
+impl VisitedSet<Pos> for GridBitSet {
+    fn insert(&mut self, state: Pos) -> bool {
+        GridBitSet::insert(self, state)
+    }
+
+    fn contains(&self, state: &Pos) -> bool {
+        GridBitSet::contains(self, state)
+    }
+}
+
+const DIRECTION_COUNT: usize = 8;
+
+/// A set of (position, direction) states of a `width` x `height` grid, e.g. to detect
+/// a walker coming back to a cell facing the same way.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DirectionalGridBitSet {
+    bits: Bits,
+    cells: GridBitSet,
+}
+
+impl DirectionalGridBitSet {
+    pub fn new(width: usize, height: usize) -> Self {
+        DirectionalGridBitSet { bits: Bits::new(width * height * DIRECTION_COUNT), cells: GridBitSet::new(width, height) }
+    }
+
+    pub fn for_grid<T>(grid: &[Vec<T>]) -> Self {
+        DirectionalGridBitSet::new(grid.first().map_or(0, |row| row.len()), grid.len())
+    }
+
+    fn index_of(&self, pos: &Pos, direction: Direction) -> Option<usize> {
+        self.cells.index_of(pos).map(|index| index * DIRECTION_COUNT + direction as usize)
+    }
+
+    pub fn insert(&mut self, pos: Pos, direction: Direction) -> bool {
+        let index = self.index_of(&pos, direction)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the set bounds"));
+        self.cells.insert(pos);
+        self.bits.set(index, true)
+    }
+
+    pub fn contains(&self, pos: &Pos, direction: Direction) -> bool {
+        self.index_of(pos, direction).is_some_and(|index| self.bits.get(index))
+    }
+
+    /// Number of (position, direction) states in the set.
+    pub fn len(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.words.fill(0);
+        self.cells.clear();
+    }
+
+    /// Positions reached in at least one direction.
+    pub fn positions(&self) -> &GridBitSet {
+        &self.cells
+    }
+}
+
+impl VisitedSet<(Pos, Direction)> for DirectionalGridBitSet {
+    fn insert(&mut self, (pos, direction): (Pos, Direction)) -> bool {
+        DirectionalGridBitSet::insert(self, pos, direction)
+    }
+
+    fn contains(&self, (pos, direction): &(Pos, Direction)) -> bool {
+        DirectionalGridBitSet::contains(self, pos, *direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_bitset() {
+        let mut a = GridBitSet::new(10, 10);
+        assert!(a.insert(Pos::new(0, 0)));
+        assert!(!a.insert(Pos::new(0, 0)));
+        a.extend([Pos::new(9, 9), Pos::new(6, 4)]);
+        assert!(!a.contains(&Pos::new(-1, 0)));
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![Pos::new(0, 0), Pos::new(6, 4), Pos::new(9, 9)]);
+
+        let mut b = GridBitSet::new(10, 10);
+        b.insert(Pos::new(6, 4));
+        assert!(b.is_subset(&a));
+        a.difference_with(&b);
+        assert_eq!(a.len(), 2);
+        a.intersect_with(&b);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn test_directional_bitset() {
+        let mut states = DirectionalGridBitSet::new(3, 3);
+        assert!(states.insert(Pos::new(1, 1), Direction::North));
+        assert!(states.insert(Pos::new(1, 1), Direction::East));
+        assert!(!states.insert(Pos::new(1, 1), Direction::North));
+        assert!(!states.contains(&Pos::new(1, 1), Direction::South));
+        assert_eq!((states.len(), states.positions().len()), (2, 1));
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::utils::parse::{sections, ParseError};
+
+/// Index of a wire in its `Circuit`.
+pub type WireId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GateKind {
+    And,
+    Or,
+    Xor,
+}
+
+impl GateKind {
+    pub fn apply(&self, a: bool, b: bool) -> bool {
+        match self {
+            GateKind::And => a & b,
+            GateKind::Or => a | b,
+            GateKind::Xor => a ^ b,
+        }
+    }
+}
+
+impl FromStr for GateKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AND" => Ok(GateKind::And),
+            "OR" => Ok(GateKind::Or),
+            "XOR" => Ok(GateKind::Xor),
+            other => Err(format!("unknown gate `{other}`")),
+        }
+    }
+}
+
+impl Display for GateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GateKind::And => "AND",
+            GateKind::Or => "OR",
+            GateKind::Xor => "XOR",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gate {
+    pub kind: GateKind,
+    pub inputs: [WireId; 2],
+    pub output: WireId,
+}
+
+/// A circuit that cannot be evaluated or checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    /// Wires whose gates depend on each other.
+    Cycle(Vec<String>),
+    /// The buses do not have the widths of an adder, with the reason.
+    NotAnAdder(String),
+}
+
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::Cycle(wires) => write!(f, "gates form a cycle through {}", wires.join(", ")),
+            CircuitError::NotAnAdder(reason) => write!(f, "not an adder: {reason}"),
+        }
+    }
+}
+
+impl Error for CircuitError {}
+
+/// A gate breaking the structure of a ripple-carry adder, its output being the wire to swap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Miswiring {
+    pub gate: Gate,
+    pub reason: &'static str,
+}
+
+/// A network of two-input gates, each wire being written by at most one gate.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    names: Vec<String>,
+    ids: HashMap<String, WireId>,
+    gates: Vec<Gate>,
+    initial: Vec<Option<bool>>,
+}
+
+impl Circuit {
+    pub fn new() -> Self {
+        Circuit { names: Vec::new(), ids: HashMap::new(), gates: Vec::new(), initial: Vec::new() }
+    }
+
+    /// Parses `x00: 1` initial values, then `x00 AND y00 -> z00` gates after a blank line.
+    /// The values may be left out.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut circuit = Circuit::new();
+        let found = sections(input);
+        let (values, gates) = match found.as_slice() {
+            [gates] => (None, gates),
+            [values, gates] => (Some(values), gates),
+            _ => return Err(ParseError::at(input, 0, "expected initial values and gates separated by a blank line")),
+        };
+
+        if let Some(values) = values {
+            values.parse(|text| {
+                let mut offset = 0;
+                for line in text.split_inclusive('\n') {
+                    let value = match line.split_once(':').map(|(name, value)| (name.trim(), value.trim())) {
+                        Some((name, "0")) => (name, false),
+                        Some((name, "1")) => (name, true),
+                        _ => return Err(ParseError::at(text, offset, "expected a `wire: 0` or `wire: 1` line")),
+                    };
+                    let wire = circuit.add_wire(value.0);
+                    circuit.initial[wire] = Some(value.1);
+                    offset += line.len();
+                }
+                Ok(())
+            })?;
+        }
+
+        gates.parse(|text| {
+            let mut offset = 0;
+            for line in text.split_inclusive('\n') {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let [a, kind, b, "->", output] = parts[..] else {
+                    return Err(ParseError::at(text, offset, "expected a `a OP b -> c` gate"));
+                };
+                let kind = kind.parse().map_err(|e: String| ParseError::at(text, offset, e))?;
+                if !circuit.add_gate(kind, a, b, output) {
+                    return Err(ParseError::at(text, offset, format!("wire `{output}` is written by two gates")));
+                }
+                offset += line.len();
+            }
+            Ok(())
+        })?;
+
+        Ok(circuit)
+    }
+
+    /// Id of the wire called `name`, created if needed.
+    pub fn add_wire(&mut self, name: &str) -> WireId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.initial.push(None);
+        id
+    }
+
+    /// Adds a gate, unless a gate already writes to `output`. Returns whether it was added.
+    pub fn add_gate(&mut self, kind: GateKind, a: &str, b: &str, output: &str) -> bool {
+        let inputs = [self.add_wire(a), self.add_wire(b)];
+        let output = self.add_wire(output);
+        if self.gates.iter().any(|gate| gate.output == output) {
+            return false;
+        }
+        self.gates.push(Gate { kind, inputs, output });
+        true
+    }
+
+    pub fn wire(&self, name: &str) -> Option<WireId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, wire: WireId) -> &str {
+        &self.names[wire]
+    }
+
+    pub fn wire_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    /// `a AND b -> c`, with the wire names.
+    pub fn describe(&self, gate: &Gate) -> String {
+        let [a, b] = gate.inputs;
+        format!("{} {} {} -> {}", self.name(a), gate.kind, self.name(b), self.name(gate.output))
+    }
+
+    /// Exchanges the outputs of the gates writing `a` and `b`.
+    pub fn swap_outputs(&mut self, a: WireId, b: WireId) {
+        for gate in self.gates.iter_mut() {
+            if gate.output == a {
+                gate.output = b;
+            } else if gate.output == b {
+                gate.output = a;
+            }
+        }
+    }
+
+    /// Wires named `prefix` then a number, e.g. `z00` to `z45`, the bit `i` being at index `i`.
+    pub fn bus(&self, prefix: &str) -> Vec<WireId> {
+        let mut bus: Vec<(usize, WireId)> = self.names.iter().enumerate()
+            .filter_map(|(id, name)| {
+                let bit = name.strip_prefix(prefix)?;
+                if !bit.bytes().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                Some((bit.parse().ok()?, id))
+            })
+            .collect();
+        bus.sort_unstable();
+        bus.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Values given by the input, by wire.
+    pub fn initial_values(&self) -> Vec<Option<bool>> {
+        self.initial.clone()
+    }
+
+    /// Writes `value` on the bus, low bit first. Bits beyond the bus width are dropped.
+    pub fn set_bus(&self, values: &mut [Option<bool>], prefix: &str, value: u128) {
+        for (bit, wire) in self.bus(prefix).into_iter().enumerate().take(128) {
+            values[wire] = Some(value >> bit & 1 == 1);
+        }
+    }
+
+    /// The number on the bus, if every bit has a value and it fits.
+    pub fn bus_value(&self, values: &[Option<bool>], prefix: &str) -> Option<u128> {
+        let bus = self.bus(prefix);
+        if bus.is_empty() || bus.len() > 128 {
+            return None;
+        }
+        bus.iter().rev().try_fold(0, |number, &wire| Some(number << 1 | values[wire]? as u128))
+    }
+
+    /// Gates sorted so that every gate comes after the ones writing its inputs.
+    pub fn topological_order(&self) -> Result<Vec<usize>, CircuitError> {
+        let mut writer = vec![None; self.wire_count()];
+        for (index, gate) in self.gates.iter().enumerate() {
+            writer[gate.output] = Some(index);
+        }
+
+        let mut readers: Vec<Vec<usize>> = vec![Vec::new(); self.gates.len()];
+        let mut waiting: Vec<usize> = vec![0; self.gates.len()];
+        for (index, gate) in self.gates.iter().enumerate() {
+            for input in gate.inputs {
+                if let Some(source) = writer[input] {
+                    readers[source].push(index);
+                    waiting[index] += 1;
+                }
+            }
+        }
+
+        // Kahn's algorithm, a gate being ready once the gates writing its inputs are
+        let mut ready: VecDeque<usize> = (0..self.gates.len()).filter(|&index| waiting[index] == 0).collect();
+        let mut order = Vec::with_capacity(self.gates.len());
+        while let Some(index) = ready.pop_front() {
+            order.push(index);
+            for &reader in &readers[index] {
+                waiting[reader] -= 1;
+                if waiting[reader] == 0 {
+                    ready.push_back(reader);
+                }
+            }
+        }
+
+        if order.len() < self.gates.len() {
+            let stuck = (0..self.gates.len())
+                .filter(|&index| waiting[index] > 0)
+                .map(|index| self.names[self.gates[index].output].clone())
+                .collect();
+            return Err(CircuitError::Cycle(stuck));
+        }
+        Ok(order)
+    }
+
+    /// Propagates `values` through the gates. Wires depending on a wire without value get none.
+    pub fn evaluate(&self, mut values: Vec<Option<bool>>) -> Result<Vec<Option<bool>>, CircuitError> {
+        values.resize(self.wire_count(), None);
+
+        for index in self.topological_order()? {
+            let gate = &self.gates[index];
+            let [a, b] = gate.inputs;
+            values[gate.output] = match (values[a], values[b]) {
+                (Some(a), Some(b)) => Some(gate.kind.apply(a, b)),
+                _ => None,
+            };
+        }
+
+        Ok(values)
+    }
+
+    /// Checks that the gates make a ripple-carry adder of `x` and `y` into `z`, returning the
+    /// gates that do not fit, sorted by output name. An empty list means the adder is correct.
+    ///
+    /// For bit `i` the adder computes `s = x XOR y`, `z = s XOR carry`, and the next carry as
+    /// `(x AND y) OR (s AND carry)`. Bit 0 has no carry in, and the last carry is the top `z` bit.
+    pub fn adder_miswirings(&self) -> Result<Vec<Miswiring>, CircuitError> {
+        let (x, y, z) = (self.bus("x"), self.bus("y"), self.bus("z"));
+        let width = x.len();
+        if width == 0 || y.len() != width || z.len() != width + 1 {
+            let message = format!("{} x bits and {} y bits for {} z bits", x.len(), y.len(), z.len());
+            return Err(CircuitError::NotAnAdder(message));
+        }
+
+        // Bit position of the input wires, and kinds of the gates reading each wire
+        let mut input_bit = vec![None; self.wire_count()];
+        for bus in [&x, &y] {
+            for (bit, &wire) in bus.iter().enumerate() {
+                input_bit[wire] = Some(bit);
+            }
+        }
+        let mut read_by: Vec<Vec<GateKind>> = vec![Vec::new(); self.wire_count()];
+        for gate in &self.gates {
+            gate.inputs.iter().for_each(|&input| read_by[input].push(gate.kind));
+        }
+        let feeds = |wire: WireId, kind: GateKind| read_by[wire].contains(&kind);
+
+        let check = |gate: &Gate| -> Option<&'static str> {
+            let [a, b] = gate.inputs;
+            let out = gate.output;
+            let first_bit = input_bit[a] == Some(0) && input_bit[b] == Some(0);
+            let reads_inputs = input_bit[a].is_some() && input_bit[b].is_some();
+
+            if reads_inputs && input_bit[a] != input_bit[b] {
+                return Some("reads x and y bits of different positions");
+            }
+            if out == z[width] {
+                let carry_kind = if width == 1 { GateKind::And } else { GateKind::Or };
+                return (gate.kind != carry_kind).then_some("the top z bit must be the last carry");
+            }
+            if z.contains(&out) && gate.kind != GateKind::Xor {
+                return Some("a z bit must be written by a XOR gate");
+            }
+
+            match gate.kind {
+                GateKind::Xor if first_bit => (out != z[0]).then_some("x00 XOR y00 must write z00"),
+                GateKind::Xor if reads_inputs => {
+                    (!feeds(out, GateKind::Xor)).then_some("x XOR y must feed the XOR gate writing the z bit")
+                }
+                GateKind::Xor => (!z.contains(&out)).then_some("a XOR gate of internal wires must write a z bit"),
+                GateKind::And if first_bit => {
+                    (!feeds(out, GateKind::Xor)).then_some("the carry of bit 0 must feed the XOR gate of bit 1")
+                }
+                GateKind::And => (!feeds(out, GateKind::Or)).then_some("an AND gate must feed the OR gate of a carry"),
+                GateKind::Or => {
+                    (!feeds(out, GateKind::Xor) || !feeds(out, GateKind::And))
+                        .then_some("a carry must feed the XOR and AND gates of the next bit")
+                }
+            }
+        };
+
+        let mut miswirings: Vec<Miswiring> = self.gates.iter()
+            .filter_map(|gate| check(gate).map(|reason| Miswiring { gate: *gate, reason }))
+            .collect();
+        miswirings.sort_by(|a, b| self.name(a.gate.output).cmp(self.name(b.gate.output)));
+        Ok(miswirings)
+    }
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Circuit::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A correct adder of `width` bits
+    fn adder(width: usize) -> String {
+        let mut gates = vec!["x00 XOR y00 -> z00".to_string(), "x00 AND y00 -> c00".to_string()];
+        for i in 1..width {
+            let carry_out = if i == width - 1 { format!("z{:02}", width) } else { format!("c{i:02}") };
+            gates.extend([
+                format!("x{i:02} XOR y{i:02} -> s{i:02}"),
+                format!("x{i:02} AND y{i:02} -> a{i:02}"),
+                format!("s{i:02} XOR c{:02} -> z{i:02}", i - 1),
+                format!("s{i:02} AND c{:02} -> b{i:02}", i - 1),
+                format!("a{i:02} OR b{i:02} -> {carry_out}"),
+            ]);
+        }
+        gates.join("\n")
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let circuit = Circuit::parse("x00: 1\nx01: 1\ny00: 0\ny01: 1\n\nx00 AND y00 -> z00\nx01 OR y01 -> t\nt XOR z00 -> z01").unwrap();
+        let values = circuit.evaluate(circuit.initial_values()).unwrap();
+        assert_eq!(circuit.bus_value(&values, "z"), Some(0b10));
+        assert_eq!(circuit.bus("x").len(), 2);
+
+        let adder = Circuit::parse(&adder(70)).unwrap();
+        let mut values = vec![None; adder.wire_count()];
+        adder.set_bus(&mut values, "x", 1 << 69 | 12345);
+        adder.set_bus(&mut values, "y", (1 << 70) - 1);
+        let values = adder.evaluate(values).unwrap();
+        assert_eq!(adder.bus_value(&values, "z"), Some((1 << 69 | 12345) + (1 << 70) - 1));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = Circuit::parse("x: 1\n\na AND b -> c\na NOR b -> d").unwrap_err();
+        assert_eq!((error.line, error.message.as_str()), (4, "unknown gate `NOR`"));
+        assert!(Circuit::parse("a AND b -> c\nb OR c -> c").is_err());
+
+        let cycle = Circuit::parse("a AND b -> c\nc OR d -> b").unwrap();
+        assert!(matches!(cycle.topological_order(), Err(CircuitError::Cycle(wires)) if wires.len() == 2));
+        assert!(matches!(cycle.adder_miswirings(), Err(CircuitError::NotAnAdder(_))));
+    }
+
+    #[test]
+    fn test_adder_miswirings() {
+        let mut circuit = Circuit::parse(&adder(8)).unwrap();
+        assert_eq!(circuit.adder_miswirings(), Ok(vec![]));
+
+        let wire = |name| circuit.wire(name).unwrap();
+        let swaps = [(wire("s02"), wire("a02")), (wire("z05"), wire("c05"))];
+        swaps.iter().for_each(|&(a, b)| circuit.swap_outputs(a, b));
+
+        let miswired: Vec<&str> = circuit.adder_miswirings().unwrap()
+            .iter()
+            .map(|miswiring| circuit.name(miswiring.gate.output))
+            .collect();
+        assert_eq!(miswired, vec!["a02", "c05", "s02", "z05"]);
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A sequence of states that starts repeating: from step `start` on, the state at step
+/// `n + length` is the same as the state at step `n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step showing the same state as `step`.
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare. Only keeps two states in memory, but computes about three
+/// times more steps than `find_cycle`.
+pub fn floyd<S: Eq + Clone>(initial: S, mut next: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = next(&initial);
+    let mut hare = next(&tortoise);
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        hare = next(&hare);
+    }
+
+    // The distance from the start to the cycle equals the one from the meeting point
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = next(&tortoise);
+    while tortoise != hare {
+        hare = next(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm. Only keeps two states in memory, and usually needs fewer steps than `floyd`.
+pub fn brent<S: Eq + Clone>(initial: S, mut next: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = next(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = next(&hare);
+        length += 1;
+    }
+
+    // Start two states `length` steps apart, they meet at the start of the cycle
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = next(&hare);
+    }
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Remembers every state until one repeats. Returns the cycle along with the states seen,
+/// the state at step `n` being `states[n]`.
+pub fn find_cycle<S: Hash + Eq + Clone>(initial: S, mut next: impl FnMut(&S) -> S) -> (Cycle, Vec<S>) {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            return (Cycle { start, length: states.len() - start }, states);
+        }
+        seen.insert(state.clone(), states.len());
+        let following = next(&state);
+        states.push(state);
+        state = following;
+    }
+}
+
+/// The state reached after `steps` steps, e.g. a billion, skipping the repetitions of the cycle.
+pub fn state_at<S: Hash + Eq + Clone>(initial: S, next: impl FnMut(&S) -> S, steps: usize) -> S {
+    let (cycle, mut states) = find_cycle(initial, next);
+    states.swap_remove(cycle.equivalent_step(steps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 2 -> 4 -> 6 -> 3 -> 5 -> 7 -> 4 ...
+    fn next(n: &u32) -> u32 {
+        if *n >= 6 { n - 3 } else { n + 2 }
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let expected = Cycle { start: 2, length: 5 };
+        assert_eq!(floyd(0, next), expected);
+        assert_eq!(brent(0, next), expected);
+        assert_eq!(find_cycle(0, next).0, expected);
+    }
+
+    #[test]
+    fn test_state_at() {
+        assert_eq!(state_at(0, next, 1), 2);
+        assert_eq!(state_at(0, next, 1_000_000_000), 5);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::utils::grid_utils::{Edge, Pos};
+
+/// Turn made when going from `a` to `b` then to `c`, with x as the column and y as the row.
+///
+/// Rows grow downwards, so `CounterClockwise` looks clockwise once the grid is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    Collinear,
+}
+
+/// Cross product of `a - origin` and `b - origin`.
+pub fn cross(origin: Pos, a: Pos, b: Pos) -> isize {
+    (a.x() - origin.x()) * (b.y() - origin.y()) - (a.y() - origin.y()) * (b.x() - origin.x())
+}
+
+pub fn orientation(a: Pos, b: Pos, c: Pos) -> Orientation {
+    match cross(a, b, c).signum() {
+        1 => Orientation::CounterClockwise,
+        -1 => Orientation::Clockwise,
+        _ => Orientation::Collinear,
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl Edge {
+    /// Number of grid points lying on the edge, both ends included.
+    pub fn lattice_points(&self) -> usize {
+        gcd(self.start.row.abs_diff(self.end.row), self.start.col.abs_diff(self.end.col)) + 1
+    }
+
+    /// Whether both edges share at least one point, touching ends and overlaps included.
+    pub fn intersects(&self, other: &Edge) -> bool {
+        let (a, b, c, d) = (self.start, self.end, other.start, other.end);
+        let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+        let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+
+        let has_collinear_end = [o1, o2, o3, o4].contains(&Orientation::Collinear);
+        if o1 != o2 && o3 != o4 && !has_collinear_end {
+            return true;
+        }
+
+        // Every remaining case has an end of one edge lying on the other one
+        self.contains(&c) || self.contains(&d) || other.contains(&a) || other.contains(&b)
+    }
+}
+
+/// Twice the area of the polygon, using the shoelace formula. Keeping it doubled stays exact.
+pub fn double_area(vertices: &[Pos]) -> usize {
+    let sum: isize = vertices.iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| a.x() * b.y() - b.x() * a.y())
+        .sum();
+
+    sum.unsigned_abs()
+}
+
+/// Number of grid points on the outline of the polygon.
+pub fn boundary_points(vertices: &[Pos]) -> usize {
+    vertices.iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&start, &end)| Edge::new(start, end).lattice_points() - 1)
+        .sum()
+}
+
+/// Number of grid points strictly inside the polygon, from Pick's theorem: A = i + b/2 - 1.
+/// A degenerate polygon, e.g. a segment walked there and back, has none.
+pub fn interior_points(vertices: &[Pos]) -> usize {
+    (double_area(vertices) + 2).saturating_sub(boundary_points(vertices)) / 2
+}
+
+/// Number of grid points inside or on the polygon, i.e. the cells covered by a dug out trench.
+pub fn enclosed_points(vertices: &[Pos]) -> usize {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+/// Convex hull of the points in counter-clockwise order, using the monotone chain algorithm.
+/// Points lying in the middle of a hull side are left out.
+pub fn convex_hull(points: &[Pos]) -> Vec<Pos> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by_key(|pos| (pos.x(), pos.y()));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<Pos> = Vec::new();
+    // Lower hull then upper hull, each one dropping points making a clockwise turn
+    for pass in [sorted.clone(), sorted.into_iter().rev().collect()] {
+        let chain_start = hull.len();
+        for pos in pass {
+            while hull.len() >= chain_start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], pos) <= 0 {
+                hull.pop();
+            }
+            hull.push(pos);
+        }
+        // The last point of a chain is the first one of the next
+        hull.pop();
+    }
+
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polygon_counts() {
+        let square = [Pos::from_xy(0, 0), Pos::from_xy(4, 0), Pos::from_xy(4, 4), Pos::from_xy(0, 4)];
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(enclosed_points(&square), 25);
+
+        let there_and_back = [Pos::from_xy(0, 0), Pos::from_xy(4, 0), Pos::from_xy(4, 2), Pos::from_xy(4, 0)];
+        assert_eq!(double_area(&there_and_back), 0);
+        assert_eq!(boundary_points(&there_and_back), 12);
+        assert_eq!(interior_points(&there_and_back), 0);
+    }
+
+    #[test]
+    fn test_segments() {
+        let diagonal = Edge::new(Pos::from_xy(0, 0), Pos::from_xy(4, 4));
+        assert_eq!(diagonal.lattice_points(), 5);
+        assert!(diagonal.intersects(&Edge::new(Pos::from_xy(0, 4), Pos::from_xy(4, 0))));
+        assert!(diagonal.intersects(&Edge::new(Pos::from_xy(4, 4), Pos::from_xy(6, 4))));
+        assert!(!diagonal.intersects(&Edge::new(Pos::from_xy(1, 0), Pos::from_xy(5, 4))));
+        assert_eq!(orientation(Pos::from_xy(0, 0), Pos::from_xy(1, 0), Pos::from_xy(1, 1)), Orientation::CounterClockwise);
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            Pos::from_xy(0, 0), Pos::from_xy(2, 0), Pos::from_xy(4, 0), Pos::from_xy(2, 2),
+            Pos::from_xy(4, 4), Pos::from_xy(0, 4), Pos::from_xy(1, 3),
+        ];
+        assert_eq!(convex_hull(&points), vec![Pos::from_xy(0, 0), Pos::from_xy(4, 0), Pos::from_xy(4, 4), Pos::from_xy(0, 4)]);
+    }
+}
@@ -0,0 +1,595 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// Undirected, unweighted graph.
+#[derive(Debug, Clone, Default)]
+pub struct Graph<N> {
+    pub nodes: HashMap<N, HashSet<N>>,
+}
+
+impl<N: Eq + Hash + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Graph { nodes: HashMap::new() }
+    }
+
+    /// Builds a graph from lines like `a-b`, `parse_node` turning each side into a node.
+    /// Lines without the separator are ignored.
+    pub fn from_edge_list<'a>(input: &'a str, separator: char, mut parse_node: impl FnMut(&'a str) -> N) -> Self {
+        let mut graph = Graph::new();
+        for (a, b) in input.lines().filter_map(|l| l.trim().split_once(separator)) {
+            graph.add_edge(parse_node(a.trim()), parse_node(b.trim()));
+        }
+        graph
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.nodes.entry(node).or_default();
+    }
+
+    pub fn has_node(&self, node: &N) -> bool {
+        self.nodes.contains_key(node)
+    }
+
+    pub fn add_edge(&mut self, node1: N, node2: N) {
+        if !self.has_edge(&node1, &node2) {
+            self.nodes.entry(node1.clone()).or_default().insert(node2.clone());
+            self.nodes.entry(node2).or_default().insert(node1);
+        }
+    }
+
+    pub fn has_edge(&self, node1: &N, node2: &N) -> bool {
+        if let Some(neighbors) = self.neighbors(node1) {
+            neighbors.contains(node2)
+        } else {
+            false
+        }
+    }
+
+    pub fn neighbors(&self, node: &N) -> Option<&HashSet<N>> {
+        self.nodes.get(node)
+    }
+
+    pub fn degree(&self, node: &N) -> usize {
+        self.neighbors(node).map_or(0, HashSet::len)
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.nodes.values().map(HashSet::len).sum::<usize>() / 2
+    }
+
+    /// Groups of nodes reachable from each other.
+    pub fn connected_components(&self) -> Vec<Vec<N>> {
+        let mut seen: HashSet<&N> = HashSet::new();
+        let mut components = Vec::new();
+
+        for root in self.nodes.keys() {
+            if !seen.insert(root) {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut queue = VecDeque::from([root]);
+            while let Some(node) = queue.pop_front() {
+                component.push(node.clone());
+                for neighbor in &self.nodes[node] {
+                    if seen.insert(neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        components
+    }
+}
+
+impl<N: Eq + Hash + Clone> Graph<N> {
+    /// Every set of three nodes all linked to each other.
+    pub fn triangles(&self) -> Vec<[N; 3]> {
+        let (nodes, adjacency) = self.indexed();
+        let mut triangles = Vec::new();
+
+        for (a, neighbors) in adjacency.iter().enumerate() {
+            for &b in neighbors.iter().filter(|&&b| b > a) {
+                for &c in neighbors.intersection(&adjacency[b]).filter(|&&c| c > b) {
+                    triangles.push([nodes[a].clone(), nodes[b].clone(), nodes[c].clone()]);
+                }
+            }
+        }
+
+        triangles
+    }
+
+    /// Every clique that cannot be extended with another node, using Bron–Kerbosch with
+    /// pivoting, started from each node in degeneracy order.
+    pub fn maximal_cliques(&self) -> Vec<Vec<N>> {
+        let (nodes, adjacency) = self.indexed();
+        let mut cliques = Vec::new();
+        let mut position = vec![0; nodes.len()];
+        let order = degeneracy_order(&adjacency);
+        for (i, &node) in order.iter().enumerate() {
+            position[node] = i;
+        }
+
+        for &node in &order {
+            let (later, earlier): (HashSet<usize>, HashSet<usize>) = adjacency[node]
+                .iter()
+                .partition(|&&neighbor| position[neighbor] > position[node]);
+            bron_kerbosch(&adjacency, &mut vec![node], later, earlier, &mut cliques);
+        }
+
+        cliques.into_iter()
+            .map(|clique| clique.into_iter().map(|i| nodes[i].clone()).collect())
+            .collect()
+    }
+
+    /// One of the largest cliques of the graph, empty if the graph has no nodes.
+    pub fn maximum_clique(&self) -> Vec<N> {
+        self.maximal_cliques().into_iter().max_by_key(Vec::len).unwrap_or_default()
+    }
+
+    // Maps the nodes to indices, which are much cheaper to hash and copy around
+    fn indexed(&self) -> (Vec<&N>, Vec<HashSet<usize>>) {
+        let nodes: Vec<&N> = self.nodes.keys().collect();
+        let index: HashMap<&N, usize> = nodes.iter().enumerate().map(|(i, &node)| (node, i)).collect();
+        let adjacency = nodes.iter()
+            .map(|&node| self.nodes[node].iter().map(|neighbor| index[neighbor]).collect())
+            .collect();
+
+        (nodes, adjacency)
+    }
+}
+
+// Repeatedly removes a node of minimal remaining degree
+fn degeneracy_order(adjacency: &[HashSet<usize>]) -> Vec<usize> {
+    let mut degrees: Vec<usize> = adjacency.iter().map(HashSet::len).collect();
+    let mut removed = vec![false; adjacency.len()];
+    let mut heap: BinaryHeap<Reverse<(usize, usize)>> = degrees.iter().enumerate().map(|(node, &degree)| Reverse((degree, node))).collect();
+    let mut order = Vec::with_capacity(adjacency.len());
+
+    while let Some(Reverse((degree, node))) = heap.pop() {
+        if removed[node] || degree != degrees[node] {
+            continue;
+        }
+
+        removed[node] = true;
+        order.push(node);
+        for &neighbor in &adjacency[node] {
+            if !removed[neighbor] {
+                degrees[neighbor] -= 1;
+                heap.push(Reverse((degrees[neighbor], neighbor)));
+            }
+        }
+    }
+
+    order
+}
+
+fn bron_kerbosch(
+    adjacency: &[HashSet<usize>],
+    r: &mut Vec<usize>,
+    mut p: HashSet<usize>,
+    mut x: HashSet<usize>,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    if p.is_empty() {
+        if x.is_empty() {
+            cliques.push(r.clone());
+        }
+        return;
+    }
+
+    // Neighbors of the pivot are covered by the recursion on the pivot or one of its non-neighbors
+    let pivot = p.union(&x)
+        .max_by_key(|&&u| p.intersection(&adjacency[u]).count())
+        .copied()
+        .unwrap();
+    let candidates: Vec<usize> = p.difference(&adjacency[pivot]).copied().collect();
+
+    for n in candidates {
+        let neighbours = &adjacency[n];
+        let p2 = p.intersection(neighbours).copied().collect();
+        let x2 = x.intersection(neighbours).copied().collect();
+        r.push(n);
+        bron_kerbosch(adjacency, r, p2, x2, cliques);
+        r.pop();
+        p.remove(&n);
+        x.insert(n);
+    }
+}
+
+/// Directed graph, each edge carrying a weight of type `W`.
+#[derive(Debug, Clone)]
+pub struct DiGraph<N, W = ()> {
+    pub nodes: HashMap<N, HashMap<N, W>>,
+}
+
+impl<N, W> Default for DiGraph<N, W> {
+    fn default() -> Self {
+        DiGraph { nodes: HashMap::new() }
+    }
+}
+
+/// Returned by [`DiGraph::topological_sort`] when the graph is not acyclic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    /// Nodes of one of the cycles, each one having an edge to the next and the last to the first.
+    pub cycle: Vec<N>,
+}
+
+impl<N: Debug> Display for CycleError<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph contains a cycle: {:?}", self.cycle)
+    }
+}
+
+impl<N: Debug> Error for CycleError<N> {}
+
+impl<N: Eq + Hash + Clone> DiGraph<N, ()> {
+    /// Builds a graph from lines like `a|b` meaning `a -> b`, `parse_node` turning each side into a node.
+    /// Lines without the separator are ignored.
+    pub fn from_edge_list<'a>(input: &'a str, separator: char, mut parse_node: impl FnMut(&'a str) -> N) -> Self {
+        let mut graph = DiGraph::new();
+        for (from, to) in input.lines().filter_map(|l| l.trim().split_once(separator)) {
+            graph.add_edge(parse_node(from.trim()), parse_node(to.trim()));
+        }
+        graph
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        self.add_weighted_edge(from, to, ());
+    }
+}
+
+impl<N: Eq + Hash + Clone, W: Clone> DiGraph<N, W> {
+    pub fn new() -> Self {
+        DiGraph::default()
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.nodes.entry(node).or_default();
+    }
+
+    pub fn has_node(&self, node: &N) -> bool {
+        self.nodes.contains_key(node)
+    }
+
+    /// Adds the edge `from -> to`, replacing its weight if it already exists.
+    pub fn add_weighted_edge(&mut self, from: N, to: N, weight: W) {
+        self.add_node(to.clone());
+        self.nodes.entry(from).or_default().insert(to, weight);
+    }
+
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        self.weight(from, to).is_some()
+    }
+
+    pub fn weight(&self, from: &N, to: &N) -> Option<&W> {
+        self.nodes.get(from).and_then(|edges| edges.get(to))
+    }
+
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.nodes.get(node).into_iter().flat_map(HashMap::keys)
+    }
+
+    pub fn predecessors<'a>(&'a self, node: &'a N) -> impl Iterator<Item = &'a N> {
+        self.nodes.iter()
+            .filter(move |(_, edges)| edges.contains_key(node))
+            .map(|(from, _)| from)
+    }
+
+    pub fn out_degree(&self, node: &N) -> usize {
+        self.nodes.get(node).map_or(0, HashMap::len)
+    }
+
+    pub fn in_degree(&self, node: &N) -> usize {
+        self.predecessors(node).count()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.nodes.values().map(HashMap::len).sum()
+    }
+
+    /// The same graph with every edge pointing the other way.
+    pub fn reversed(&self) -> Self {
+        let mut reversed = DiGraph::new();
+        for (from, edges) in &self.nodes {
+            reversed.add_node(from.clone());
+            for (to, weight) in edges {
+                reversed.add_weighted_edge(to.clone(), from.clone(), weight.clone());
+            }
+        }
+        reversed
+    }
+
+    /// The graph restricted to `nodes` and the edges between them.
+    pub fn subgraph(&self, nodes: &[N]) -> Self {
+        let kept: HashSet<&N> = nodes.iter().collect();
+        let mut subgraph = DiGraph::new();
+
+        for node in nodes {
+            subgraph.add_node(node.clone());
+            for (to, weight) in self.nodes.get(node).into_iter().flatten() {
+                if kept.contains(to) {
+                    subgraph.add_weighted_edge(node.clone(), to.clone(), weight.clone());
+                }
+            }
+        }
+
+        subgraph
+    }
+
+    /// Orders the nodes so that every edge goes from an earlier node to a later one.
+    pub fn topological_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        let mut in_degrees: HashMap<&N, usize> = self.nodes.keys().map(|node| (node, 0)).collect();
+        for to in self.nodes.values().flat_map(HashMap::keys) {
+            *in_degrees.get_mut(to).unwrap() += 1;
+        }
+
+        let mut queue: VecDeque<&N> = in_degrees.iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&node, _)| node)
+            .collect();
+        let mut sorted = Vec::with_capacity(self.nodes.len());
+
+        while let Some(node) = queue.pop_front() {
+            sorted.push(node.clone());
+            for to in self.nodes[node].keys() {
+                let degree = in_degrees.get_mut(to).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        if sorted.len() == self.nodes.len() {
+            return Ok(sorted);
+        }
+
+        // Every node left has a predecessor that is also left, walking them back must loop
+        let remaining: HashSet<&N> = in_degrees.into_iter()
+            .filter(|&(_, degree)| degree > 0)
+            .map(|(node, _)| node)
+            .collect();
+        let mut walk: Vec<&N> = vec![*remaining.iter().next().unwrap()];
+        let mut positions: HashMap<&N, usize> = HashMap::from([(walk[0], 0)]);
+
+        loop {
+            let current = walk[walk.len() - 1];
+            let previous = self.predecessors(current).find(|node| remaining.contains(node)).unwrap();
+
+            if let Some(&start) = positions.get(previous) {
+                let cycle = walk[start..].iter().rev().map(|&node| node.clone()).collect();
+                return Err(CycleError { cycle });
+            }
+
+            positions.insert(previous, walk.len());
+            walk.push(previous);
+        }
+    }
+
+    /// Groups of nodes that can all reach each other, using Kosaraju's algorithm.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        // First pass: order the nodes by the time their depth-first exploration finishes
+        let mut finished: Vec<&N> = Vec::with_capacity(self.nodes.len());
+        let mut seen: HashSet<&N> = HashSet::new();
+
+        for root in self.nodes.keys() {
+            if !seen.insert(root) {
+                continue;
+            }
+
+            let mut stack = vec![(root, self.nodes[root].keys())];
+            while let Some((node, successors)) = stack.last_mut() {
+                let node = *node;
+                let next = successors.find(|&next| !seen.contains(next));
+                match next {
+                    Some(next) => {
+                        seen.insert(next);
+                        stack.push((next, self.nodes[next].keys()));
+                    }
+                    None => {
+                        finished.push(node);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        // Second pass: explore the reversed graph, latest finished first
+        let mut reversed: HashMap<&N, Vec<&N>> = HashMap::new();
+        for (from, edges) in &self.nodes {
+            for to in edges.keys() {
+                reversed.entry(to).or_default().push(from);
+            }
+        }
+        let mut assigned: HashSet<&N> = HashSet::new();
+        let mut components = Vec::new();
+
+        for root in finished.into_iter().rev() {
+            if !assigned.insert(root) {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                component.push(node.clone());
+                for &previous in reversed.get(node).into_iter().flatten() {
+                    if assigned.insert(previous) {
+                        stack.push(previous);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        components
+    }
+}
+
+/// Nodes and edges to draw in red when exporting a graph to the Graphviz DOT format.
+#[derive(Debug, Clone)]
+pub struct Highlight<N> {
+    pub nodes: HashSet<N>,
+    pub edges: HashSet<(N, N)>,
+}
+
+impl<N> Default for Highlight<N> {
+    fn default() -> Self {
+        Highlight { nodes: HashSet::new(), edges: HashSet::new() }
+    }
+}
+
+impl<N: Eq + Hash> Highlight<N> {
+    pub fn new() -> Self {
+        Highlight::default()
+    }
+
+    pub fn with_nodes(mut self, nodes: impl IntoIterator<Item = N>) -> Self {
+        self.nodes.extend(nodes);
+        self
+    }
+
+    pub fn with_edges(mut self, edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        self.edges.extend(edges);
+        self
+    }
+
+    fn node_attributes(&self, node: &N) -> &'static str {
+        if self.nodes.contains(node) { " [color=red, fontcolor=red, penwidth=2]" } else { "" }
+    }
+
+    fn edge_attributes(&self, from: &N, to: &N, directed: bool) -> &'static str
+    where
+        N: Clone,
+    {
+        let highlighted = self.edges.contains(&(from.clone(), to.clone()))
+            || (!directed && self.edges.contains(&(to.clone(), from.clone())));
+        if highlighted { "color=red, penwidth=2" } else { "" }
+    }
+}
+
+fn dot_id(node: &impl Display) -> String {
+    format!("\"{}\"", node.to_string().replace('"', "\\\""))
+}
+
+fn dot_document(kind: &str, mut nodes: Vec<String>, mut edges: Vec<String>) -> String {
+    // Sorted so that exporting the same graph twice gives the same file
+    nodes.sort();
+    edges.sort();
+
+    let mut dot = format!("{kind} {{\n");
+    for line in nodes.iter().chain(edges.iter()) {
+        dot.push_str(&format!("  {line};\n"));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn dot_edge(from: String, arrow: &str, to: String, attributes: &[&str]) -> String {
+    let attributes: Vec<&str> = attributes.iter().copied().filter(|a| !a.is_empty()).collect();
+    if attributes.is_empty() {
+        format!("{from} {arrow} {to}")
+    } else {
+        format!("{from} {arrow} {to} [{}]", attributes.join(", "))
+    }
+}
+
+impl<N: Eq + Hash + Clone + Display> Graph<N> {
+    /// Renders the graph in the Graphviz DOT format, e.g. for `dot -Tsvg`.
+    pub fn to_dot(&self, highlight: &Highlight<N>) -> String {
+        let nodes = self.nodes.keys()
+            .map(|node| format!("{}{}", dot_id(node), highlight.node_attributes(node)))
+            .collect();
+
+        let edges = self.nodes.iter()
+            .flat_map(|(from, neighbors)| neighbors.iter().map(move |to| (from, to)))
+            .filter(|(from, to)| dot_id(from) < dot_id(to))
+            .map(|(from, to)| dot_edge(dot_id(from), "--", dot_id(to), &[highlight.edge_attributes(from, to, false)]))
+            .collect();
+
+        dot_document("graph", nodes, edges)
+    }
+}
+
+impl<N: Eq + Hash + Clone + Display, W: Clone> DiGraph<N, W> {
+    /// Renders the graph in the Graphviz DOT format, `edge_label` giving the optional text
+    /// written along each edge.
+    pub fn to_dot(&self, highlight: &Highlight<N>, edge_label: impl Fn(&W) -> Option<String>) -> String {
+        let nodes = self.nodes.keys()
+            .map(|node| format!("{}{}", dot_id(node), highlight.node_attributes(node)))
+            .collect();
+
+        let edges = self.nodes.iter()
+            .flat_map(|(from, edges)| edges.iter().map(move |(to, weight)| (from, to, weight)))
+            .map(|(from, to, weight)| {
+                let label = edge_label(weight).map(|label| format!("label={}", dot_id(&label))).unwrap_or_default();
+                dot_edge(dot_id(from), "->", dot_id(to), &[&label, highlight.edge_attributes(from, to, true)])
+            })
+            .collect();
+
+        dot_document("digraph", nodes, edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = DiGraph::from_edge_list("a|b\nb|c\na|c", '|', |s| s);
+        assert_eq!(graph.topological_sort(), Ok(vec!["a", "b", "c"]));
+
+        let cyclic = DiGraph::from_edge_list("a|b\nb|c\nc|b\nc|d", '|', |s| s);
+        let mut cycle = cyclic.topological_sort().unwrap_err().cycle;
+        cycle.sort();
+        assert_eq!(cycle, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_components() {
+        let graph = DiGraph::from_edge_list("a|b\nb|a\nb|c\nc|d\nd|c\nd|e", '|', |s| s);
+        let mut components: Vec<Vec<&str>> = graph.strongly_connected_components()
+            .into_iter()
+            .map(|mut c| { c.sort(); c })
+            .collect();
+        components.sort();
+        assert_eq!(components, vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+
+        let undirected = Graph::from_edge_list("a-b\nc-d\nd-e", '-', |s| s);
+        assert_eq!(undirected.connected_components().len(), 2);
+        assert_eq!(undirected.degree(&"d"), 2);
+    }
+
+    #[test]
+    fn test_cliques() {
+        let graph = Graph::from_edge_list("a-b\na-c\nb-c\nc-d\nb-d\na-d\nd-e\ne-f", '-', |s| s);
+
+        let mut maximum = graph.maximum_clique();
+        maximum.sort();
+        assert_eq!(maximum, vec!["a", "b", "c", "d"]);
+        assert_eq!(graph.maximal_cliques().len(), 3);
+        assert_eq!(graph.triangles().len(), 4);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let graph = Graph::from_edge_list("a-b\nb-c", '-', |s| s);
+        let highlight = Highlight::new().with_nodes(["a"]).with_edges([("c", "b")]);
+        assert_eq!(
+            graph.to_dot(&highlight),
+            "graph {\n  \"a\" [color=red, fontcolor=red, penwidth=2];\n  \"b\";\n  \"c\";\n  \"a\" -- \"b\";\n  \"b\" -- \"c\" [color=red, penwidth=2];\n}\n"
+        );
+
+        let mut directed: DiGraph<&str, u32> = DiGraph::new();
+        directed.add_weighted_edge("a", "b", 3);
+        assert!(directed.to_dot(&Highlight::new(), |w| Some(w.to_string())).contains("\"a\" -> \"b\" [label=\"3\"]"));
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+use crate::utils::grid_utils::{get_value_from_grid_pos, Direction, Pos};
+
+/// Behaviour shared by the dense, toroidal and sparse grids.
+///
+/// Every grid maps a position to the cell it designates through `normalize`, so neighbor
+/// queries and rendering only have to be written once.
+pub trait GridLike {
+    type Cell;
+
+    /// Canonical position of the cell designated by `pos`, or `None` if it is outside the grid.
+    fn normalize(&self, pos: Pos) -> Option<Pos>;
+
+    fn get(&self, pos: Pos) -> Option<&Self::Cell>;
+
+    fn get_mut(&mut self, pos: Pos) -> Option<&mut Self::Cell>;
+
+    /// Top-left and bottom-right positions of the area holding cells, `None` when empty.
+    fn bounds(&self) -> Option<(Pos, Pos)>;
+
+    fn contains(&self, pos: Pos) -> bool {
+        self.normalize(pos).is_some()
+    }
+
+    fn neighbor(&self, pos: Pos, direction: Direction) -> Option<Pos> {
+        self.normalize(pos.neighbor(direction))
+    }
+
+    fn neighbors(&self, pos: Pos, directions: &[Direction]) -> Vec<Pos> {
+        directions.iter()
+            .filter_map(|&direction| self.neighbor(pos, direction))
+            .collect()
+    }
+
+    /// One line per row of the bounds, `to_char` getting `None` for cells a sparse grid lacks.
+    fn render(&self, to_char: impl Fn(Option<&Self::Cell>) -> char) -> String {
+        let Some((top_left, bottom_right)) = self.bounds() else {
+            return String::new();
+        };
+
+        (top_left.row..=bottom_right.row)
+            .map(|row| {
+                (top_left.col..=bottom_right.col)
+                    .map(|col| to_char(self.get(Pos::new(row, col))))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A bounded rectangular grid, stored row after row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid { cells: vec![fill; width * height], width, height }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "every row of a grid must have the same length");
+
+        Grid { cells: rows.into_iter().flatten().collect(), width, height }
+    }
+
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.height).flat_map(move |row| (0..self.width).map(move |col| Pos::new(row as isize, col as isize)))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Positions alongside their cell, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Pos> {
+        self.iter().find(|(_, value)| predicate(value)).map(|(pos, _)| pos)
+    }
+
+    pub fn set(&mut self, pos: Pos, value: T) {
+        self[pos] = value;
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        let row = usize::try_from(pos.row).ok().filter(|&row| row < self.height)?;
+        let col = usize::try_from(pos.col).ok().filter(|&col| col < self.width)?;
+        Some(row * self.width + col)
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> Self {
+        Grid::from_rows(input.lines().map(|line| line.chars().collect()).collect())
+    }
+}
+
+impl<T> GridLike for Grid<T> {
+    type Cell = T;
+
+    fn normalize(&self, pos: Pos) -> Option<Pos> {
+        self.index_of(pos).map(|_| pos)
+    }
+
+    fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|index| &self.cells[index])
+    }
+
+    fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|index| &mut self.cells[index])
+    }
+
+    fn bounds(&self) -> Option<(Pos, Pos)> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        Some((Pos::new(0, 0), Pos::new(self.height as isize - 1, self.width as isize - 1)))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos).unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+// The nested vectors built by `parse_grid` are dense grids too
+impl<T> GridLike for Vec<Vec<T>> {
+    type Cell = T;
+
+    fn normalize(&self, pos: Pos) -> Option<Pos> {
+        get_value_from_grid_pos(self, pos).map(|_| pos)
+    }
+
+    fn get(&self, pos: Pos) -> Option<&T> {
+        get_value_from_grid_pos(self, pos)
+    }
+
+    fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        let row = usize::try_from(pos.row).ok()?;
+        let col = usize::try_from(pos.col).ok()?;
+        self.as_mut_slice().get_mut(row).and_then(|line| line.get_mut(col))
+    }
+
+    fn bounds(&self) -> Option<(Pos, Pos)> {
+        let width = self.iter().map(|line| line.len()).max().filter(|&width| width > 0)?;
+        Some((Pos::new(0, 0), Pos::new(self.len() as isize - 1, width as isize - 1)))
+    }
+}
+
+/// A grid whose opposite edges are glued together: leaving on one side enters on the other.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TorusGrid<T> {
+    grid: Grid<T>,
+}
+
+impl<T: Clone> TorusGrid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        TorusGrid { grid: Grid::new(width, height, fill) }
+    }
+}
+
+impl<T> TorusGrid<T> {
+    pub fn width(&self) -> usize { self.grid.width }
+
+    pub fn height(&self) -> usize { self.grid.height }
+
+    /// The position inside the grid matching `pos` once wrapped around the edges.
+    pub fn wrap(&self, pos: Pos) -> Pos {
+        Pos::new(pos.row.rem_euclid(self.grid.height as isize), pos.col.rem_euclid(self.grid.width as isize))
+    }
+
+    /// The underlying bounded grid.
+    pub fn inner(&self) -> &Grid<T> {
+        &self.grid
+    }
+}
+
+impl<T> From<Grid<T>> for TorusGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        TorusGrid { grid }
+    }
+}
+
+impl<T> GridLike for TorusGrid<T> {
+    type Cell = T;
+
+    fn normalize(&self, pos: Pos) -> Option<Pos> {
+        if self.grid.cells.is_empty() {
+            return None;
+        }
+        Some(self.wrap(pos))
+    }
+
+    fn get(&self, pos: Pos) -> Option<&T> {
+        self.normalize(pos).and_then(|pos| self.grid.get(pos))
+    }
+
+    fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.normalize(pos).and_then(|pos| self.grid.get_mut(pos))
+    }
+
+    fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.grid.bounds()
+    }
+}
+
+impl<T> Index<Pos> for TorusGrid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        &self.grid[self.wrap(pos)]
+    }
+}
+
+impl<T> IndexMut<Pos> for TorusGrid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let pos = self.wrap(pos);
+        &mut self.grid[pos]
+    }
+}
+
+/// An unbounded grid only storing the cells that were set, for puzzles on an infinite plane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pos, T>,
+    bounds: Option<(Pos, Pos)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid { cells: HashMap::new(), bounds: None }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cells of `input` for which `keep` returns a value, e.g. only the `#` of a drawing.
+    pub fn parse(input: &str, keep: impl Fn(char) -> Option<T>) -> Self {
+        let mut grid = SparseGrid::new();
+        for (row, line) in input.lines().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                if let Some(value) = keep(c) {
+                    grid.insert(Pos::new(row as isize, col as isize), value);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn insert(&mut self, pos: Pos, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((min, max)) => (
+                Pos::new(min.row.min(pos.row), min.col.min(pos.col)),
+                Pos::new(max.row.max(pos.row), max.col.max(pos.col)),
+            ),
+        });
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Pos) -> Option<T> {
+        let removed = self.cells.remove(&pos);
+
+        // Only a cell lying on the bounding box can shrink it
+        if let (Some(_), Some((min, max))) = (&removed, self.bounds) {
+            if [min.row, max.row].contains(&pos.row) || [min.col, max.col].contains(&pos.col) {
+                self.bounds = self.compute_bounds();
+            }
+        }
+        removed
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Pos, &T)> {
+        self.cells.iter()
+    }
+
+    fn compute_bounds(&self) -> Option<(Pos, Pos)> {
+        let min_row = self.cells.keys().map(|pos| pos.row).min()?;
+        let max_row = self.cells.keys().map(|pos| pos.row).max()?;
+        let min_col = self.cells.keys().map(|pos| pos.col).min()?;
+        let max_col = self.cells.keys().map(|pos| pos.col).max()?;
+
+        Some((Pos::new(min_row, min_col), Pos::new(max_row, max_col)))
+    }
+}
+
+impl<T> GridLike for SparseGrid<T> {
+    type Cell = T;
+
+    fn normalize(&self, pos: Pos) -> Option<Pos> {
+        Some(pos)
+    }
+
+    fn get(&self, pos: Pos) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::grid_utils::CARDINAL_DIRECTIONS;
+
+    #[test]
+    fn test_grid() {
+        let grid = Grid::parse("#.\n.S");
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.find(|&c| c == 'S'), Some(Pos::new(1, 1)));
+        assert_eq!(grid.neighbors(Pos::new(0, 0), &CARDINAL_DIRECTIONS), vec![Pos::new(0, 1), Pos::new(1, 0)]);
+        assert_eq!(grid.render(|c| *c.unwrap()), "#.\n.S");
+    }
+
+    #[test]
+    fn test_torus_grid() {
+        let mut torus = TorusGrid::new(3, 2, 0);
+        torus[Pos::new(-1, 4)] += 1;
+        assert_eq!(torus.wrap(Pos::new(-1, 4)), Pos::new(1, 1));
+        assert_eq!(torus.get(Pos::new(3, 1)), Some(&1));
+        assert_eq!(torus.neighbors(Pos::new(0, 0), &CARDINAL_DIRECTIONS).len(), 4);
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut sparse = SparseGrid::parse("#..\n..#", |c| (c == '#').then_some(()));
+        sparse.insert(Pos::new(-1, 0), ());
+        assert_eq!(sparse.bounds(), Some((Pos::new(-1, 0), Pos::new(1, 2))));
+        assert_eq!(sparse.render(|c| if c.is_some() { '#' } else { '.' }), "#..\n#..\n..#");
+
+        sparse.remove(Pos::new(-1, 0));
+        assert_eq!(sparse.bounds(), Some((Pos::new(0, 0), Pos::new(1, 2))));
+    }
+}
@@ -0,0 +1,582 @@
+use std::collections::HashSet;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::utils::bitset::{DirectionalGridBitSet, GridBitSet};
+use crate::utils::search::{self, VisitedSet};
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub fn get_axis(&self) -> usize {
+        match self {
+            Self::North | Self::South => 0,
+            Self::NorthEast | Direction::SouthWest => 2,
+            Self::West | Self::East => 1,
+            Self::SouthEast | Direction::NorthWest => 3,
+        }
+    }
+
+    /// Parses the usual arrow characters (`^`, `>`, `v`, `<`) into a cardinal direction.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Self::North),
+            '>' => Some(Self::East),
+            'v' => Some(Self::South),
+            '<' => Some(Self::West),
+            _ => None,
+        }
+    }
+
+    /// Rotates the direction by 90 degrees clockwise.
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Self::North => Self::East,
+            Self::NorthEast => Self::SouthEast,
+            Self::East => Self::South,
+            Self::SouthEast => Self::SouthWest,
+            Self::South => Self::West,
+            Self::SouthWest => Self::NorthWest,
+            Self::West => Self::North,
+            Self::NorthWest => Self::NorthEast,
+        }
+    }
+
+    /// Rotates the direction by 90 degrees counter-clockwise.
+    pub fn turn_left(&self) -> Direction {
+        self.opposite().turn_right()
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    /// The step taken when moving one cell in this direction.
+    pub fn offset(&self) -> Pos {
+        match self {
+            Self::North => Pos::new(-1, 0),
+            Self::NorthEast => Pos::new(-1, 1),
+            Self::East => Pos::new(0, 1),
+            Self::SouthEast => Pos::new(1, 1),
+            Self::South => Pos::new(1, 0),
+            Self::SouthWest => Pos::new(1, -1),
+            Self::West => Pos::new(0, -1),
+            Self::NorthWest => Pos::new(-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        DIAGONAL_DIRECTIONS.contains(self)
+    }
+
+    pub fn cardinal() -> impl Iterator<Item = Direction> {
+        CARDINAL_DIRECTIONS.into_iter()
+    }
+
+    pub fn diagonal() -> impl Iterator<Item = Direction> {
+        DIAGONAL_DIRECTIONS.into_iter()
+    }
+
+    pub fn all() -> impl Iterator<Item = Direction> {
+        ALL_DIRECTIONS.into_iter()
+    }
+}
+
+pub const CARDINAL_DIRECTIONS: [Direction; 4] = [
+    Direction::West,
+    Direction::North,
+    Direction::East,
+    Direction::South,
+];
+
+pub const DIAGONAL_DIRECTIONS: [Direction; 4] = [
+    Direction::NorthWest,
+    Direction::NorthEast,
+    Direction::SouthEast,
+    Direction::SouthWest,
+];
+
+pub const ALL_DIRECTIONS: [Direction; 8] = [
+    Direction::North,
+    Direction::NorthEast,
+    Direction::East,
+    Direction::SouthEast,
+    Direction::South,
+    Direction::SouthWest,
+    Direction::West,
+    Direction::NorthWest,
+];
+
+/// A cell of a grid stored as rows of columns, i.e. `grid[pos.row][pos.col]`.
+/// Rows grow downwards (south) and columns grow to the right (east).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Ord, PartialOrd)]
+pub struct Pos {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Add for Pos {
+    type Output = Pos;
+
+    fn add(self, other: Pos) -> Pos {
+        Pos::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, other: Pos) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, other: Pos) -> Pos {
+        Pos::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl SubAssign for Pos {
+    fn sub_assign(&mut self, other: Pos) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Pos {
+        Pos::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<isize> for Pos {
+    type Output = Pos;
+
+    fn mul(self, factor: isize) -> Pos {
+        Pos::new(self.row * factor, self.col * factor)
+    }
+}
+
+impl Pos {
+    pub fn new(row: isize, col: isize) -> Self { Pos { row, col } }
+
+    /// Builds a position from cartesian coordinates, `x` being the column and `y` the row.
+    pub fn from_xy(x: isize, y: isize) -> Self { Pos { row: y, col: x } }
+
+    pub fn x(&self) -> isize { self.col }
+
+    pub fn y(&self) -> isize { self.row }
+
+    pub fn neighbor(&self, direction: Direction) -> Pos {
+        *self + direction.offset()
+    }
+
+    pub fn neighbor_in_grid(&self, direction: Direction, grid: &[Vec<char>]) -> Option<(Pos, char)> {
+        let neighbor = self.neighbor(direction);
+
+        get_value_from_grid_pos(grid, neighbor).map(|&c| (neighbor, c))
+    }
+
+    pub fn neighbors(&self, directions: &[Direction]) -> Vec<Pos> {
+        directions.iter()
+            .map(|&direction| self.neighbor(direction))
+            .collect()
+    }
+
+    pub fn manhattan(&self, other: &Pos) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    pub fn chebyshev(&self, other: &Pos) -> usize {
+        self.row.abs_diff(other.row).max(self.col.abs_diff(other.col))
+    }
+
+    /// All positions at a Manhattan distance of at most `radius`, including `self`.
+    pub fn within_manhattan(self, radius: usize) -> impl Iterator<Item = Pos> {
+        let r = radius as isize;
+        (-r..=r).flat_map(move |d_row| {
+            let span = r - d_row.abs();
+            (-span..=span).map(move |d_col| self + Pos::new(d_row, d_col))
+        })
+    }
+
+    /// All positions at a Chebyshev distance of at most `radius`, including `self`.
+    pub fn within_chebyshev(self, radius: usize) -> impl Iterator<Item = Pos> {
+        let r = radius as isize;
+        (-r..=r).flat_map(move |d_row| (-r..=r).map(move |d_col| self + Pos::new(d_row, d_col)))
+    }
+}
+
+pub fn is_in_boundaries(grid: &[Vec<char>], pos: Pos) -> bool {
+    get_value_from_grid_pos(grid, pos).is_some()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub start: Pos,
+    pub end: Pos,
+}
+
+impl Edge {
+    // Constructor method to create a new Edge
+    pub fn new(start: Pos, end: Pos) -> Self { Edge { start, end } }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        let Edge { start, end } = self;
+
+        let is_between = |val, min, max| val >= min && val <= max;
+
+        if start.row == end.row && start.row == pos.row {
+            // Horizontal edge
+            let (min_col, max_col) = if start.col < end.col { (start.col, end.col) } else { (end.col, start.col) };
+            return is_between(pos.col, min_col, max_col);
+        }
+        if start.col == end.col && start.col == pos.col {
+            // Vertical edge
+            let (min_row, max_row) = if start.row < end.row { (start.row, end.row) } else { (end.row, start.row) };
+            return is_between(pos.row, min_row, max_row);
+        }
+
+        // Diagonal edge
+        let cross_product = (pos.col - start.col) * (end.row - start.row)
+            - (pos.row - start.row) * (end.col - start.col);
+
+        if cross_product != 0 {
+            return false;
+        }
+
+        let (min_row, max_row) = if start.row < end.row { (start.row, end.row) } else { (end.row, start.row) };
+        let (min_col, max_col) = if start.col < end.col { (start.col, end.col) } else { (end.col, start.col) };
+        is_between(pos.row, min_row, max_row) && is_between(pos.col, min_col, max_col)
+    }
+
+    pub fn length(&self) -> f64 {
+        let d_row = (self.end.row - self.start.row).pow(2);
+        let d_col = (self.end.col - self.start.col).pow(2);
+        ((d_row + d_col) as f64).sqrt()
+    }
+}
+
+pub fn parse_grid(input: &str) -> Vec<Vec<char>>
+{
+    input.lines()
+        .map(|line| {
+            line.chars()
+                .collect::<Vec<char>>()
+        })
+        .collect()
+}
+
+pub fn get_value_from_grid<T>(grid: &[Vec<T>], row: usize, col: usize) -> Option<&T> {
+    grid.get(row).and_then(|line| line.get(col))
+}
+
+pub fn get_value_from_grid_pos<T>(grid: &[Vec<T>], pos: Pos) -> Option<&T> {
+    let row = usize::try_from(pos.row).ok()?;
+    let col = usize::try_from(pos.col).ok()?;
+    get_value_from_grid(grid, row, col)
+}
+
+// Position and, for mazes where turning costs something, the direction being faced
+type MazeState = (Pos, Option<Direction>);
+
+// States already expanded by a maze search, as bits rather than hashed states
+struct MazeVisited {
+    cells: GridBitSet,
+    facing: DirectionalGridBitSet,
+}
+
+impl MazeVisited {
+    fn for_grid(grid: &[Vec<char>]) -> Self {
+        MazeVisited { cells: GridBitSet::for_grid(grid), facing: DirectionalGridBitSet::for_grid(grid) }
+    }
+}
+
+impl VisitedSet<MazeState> for MazeVisited {
+    fn insert(&mut self, (pos, facing): MazeState) -> bool {
+        match facing {
+            None => self.cells.insert(pos),
+            Some(direction) => self.facing.insert(pos, direction),
+        }
+    }
+
+    fn contains(&self, (pos, facing): &MazeState) -> bool {
+        match facing {
+            None => self.cells.contains(pos),
+            Some(direction) => self.facing.contains(pos, *direction),
+        }
+    }
+}
+
+/// A grid maze walked from a start to an end cell, with configurable walls and move costs.
+///
+/// Without a start direction every step to an adjacent open cell costs `step_cost`. With one,
+/// the walker can only move forward and turning by 90 degrees in place costs `turn_cost`. Both
+/// costs must be positive, as the searches expect every move to cost something.
+pub struct Maze {
+    grid: Vec<Vec<char>>,
+    pub start: Pos,
+    pub end: Pos,
+    is_wall: Box<dyn Fn(char) -> bool>,
+    step_cost: usize,
+    turn_cost: usize,
+    start_direction: Option<Direction>,
+}
+
+pub struct MazeBuilder {
+    is_wall: Box<dyn Fn(char) -> bool>,
+    start_marker: char,
+    end_marker: char,
+    start: Option<Pos>,
+    end: Option<Pos>,
+    step_cost: usize,
+    turn_cost: usize,
+    start_direction: Option<Direction>,
+}
+
+impl Default for MazeBuilder {
+    fn default() -> Self {
+        MazeBuilder {
+            is_wall: Box::new(|c| c == '#'),
+            start_marker: 'S',
+            end_marker: 'E',
+            start: None,
+            end: None,
+            step_cost: 1,
+            turn_cost: 1,
+            start_direction: None,
+        }
+    }
+}
+
+impl MazeBuilder {
+    pub fn walls(mut self, is_wall: impl Fn(char) -> bool + 'static) -> Self {
+        self.is_wall = Box::new(is_wall);
+        self
+    }
+
+    /// Characters marking the start and end cells when parsing the grid.
+    pub fn markers(mut self, start: char, end: char) -> Self {
+        self.start_marker = start;
+        self.end_marker = end;
+        self
+    }
+
+    /// Fixed start cell, taking precedence over the start marker.
+    pub fn start(mut self, start: Pos) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Fixed end cell, taking precedence over the end marker.
+    pub fn end(mut self, end: Pos) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn step_cost(mut self, cost: usize) -> Self {
+        assert!(cost > 0, "the step cost of a maze must be positive");
+        self.step_cost = cost;
+        self
+    }
+
+    pub fn turn_cost(mut self, cost: usize) -> Self {
+        assert!(cost > 0, "the turn cost of a maze must be positive");
+        self.turn_cost = cost;
+        self
+    }
+
+    pub fn start_direction(mut self, direction: Direction) -> Self {
+        self.start_direction = Some(direction);
+        self
+    }
+
+    pub fn parse(self, input: &str) -> Maze {
+        self.build(parse_grid(input))
+    }
+
+    /// Panics when the start or end cell is neither given nor marked in the grid.
+    pub fn build(self, grid: Vec<Vec<char>>) -> Maze {
+        let find_marker = |fixed: Option<Pos>, marker: char, name: &str| {
+            fixed
+                .or_else(|| grid.iter().enumerate().find_map(|(row, line)| {
+                    line.iter().position(|&c| c == marker).map(|col| Pos::new(row as isize, col as isize))
+                }))
+                .unwrap_or_else(|| panic!("no {name} cell given and no '{marker}' marker in the maze"))
+        };
+
+        let start = find_marker(self.start, self.start_marker, "start");
+        let end = find_marker(self.end, self.end_marker, "end");
+
+        Maze {
+            grid,
+            start,
+            end,
+            is_wall: self.is_wall,
+            step_cost: self.step_cost,
+            turn_cost: self.turn_cost,
+            start_direction: self.start_direction,
+        }
+    }
+}
+
+// The reindeer maze of 2024 day 16: starts facing east, turning costs a thousand points.
+impl From<&str> for Maze {
+    fn from(value: &str) -> Self {
+        Maze::builder()
+            .turn_cost(1000)
+            .start_direction(Direction::East)
+            .parse(value)
+    }
+}
+
+impl Maze {
+    pub fn builder() -> MazeBuilder {
+        MazeBuilder::default()
+    }
+
+    pub fn grid(&self) -> &[Vec<char>] {
+        &self.grid
+    }
+
+    pub fn is_open(&self, pos: Pos) -> bool {
+        get_value_from_grid_pos(&self.grid, pos).is_some_and(|&c| !(self.is_wall)(c))
+    }
+
+    pub fn set_cell(&mut self, pos: Pos, value: char) {
+        self.grid[pos.row as usize][pos.col as usize] = value;
+    }
+
+    fn moves(&self, &(position, facing): &MazeState) -> Vec<MazeState> {
+        match facing {
+            None => Direction::cardinal()
+                .map(|direction| position.neighbor(direction))
+                .filter(|&neighbor| self.is_open(neighbor))
+                .map(|neighbor| (neighbor, None))
+                .collect(),
+            Some(direction) => {
+                let mut moves = vec![
+                    (position, Some(direction.turn_left())),
+                    (position, Some(direction.turn_right())),
+                ];
+
+                let forward = position.neighbor(direction);
+                if self.is_open(forward) {
+                    moves.push((forward, facing));
+                }
+
+                moves
+            }
+        }
+    }
+
+    fn move_cost(&self, from: &MazeState, to: &MazeState) -> usize {
+        if from.0 == to.0 { self.turn_cost } else { self.step_cost }
+    }
+
+    fn search(&self) -> search::SearchResult<MazeState> {
+        search::dijkstra_with_visited(
+            (self.start, self.start_direction),
+            |state| self.moves(state),
+            |from, to| self.move_cost(from, to),
+            |&(position, _)| position == self.end,
+            &mut MazeVisited::for_grid(&self.grid),
+        )
+    }
+
+    pub fn shortest_path_cost(&self) -> Option<usize> {
+        self.search().cost()
+    }
+
+    /// Cells of one of the cheapest paths, from the start to the end.
+    pub fn shortest_path(&self) -> Option<Vec<Pos>> {
+        let mut path = self.search().path()?;
+        path.dedup_by_key(|(position, _)| *position);
+
+        Some(path.into_iter().map(|(position, _)| position).collect())
+    }
+
+    /// Number of tiles that are part of at least one of the cheapest paths.
+    pub fn best_path_tiles(&self) -> usize {
+        search::dijkstra_all_with_visited(
+            (self.start, self.start_direction),
+            |state| self.moves(state),
+            |from, to| self.move_cost(from, to),
+            |&(position, _)| position == self.end,
+            &mut MazeVisited::for_grid(&self.grid),
+        )
+            .states_on_paths()
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect::<HashSet<Pos>>()
+            .len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        for direction in Direction::all() {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::from_char('<'), Some(Direction::West));
+    }
+
+    #[test]
+    fn test_distances() {
+        let origin = Pos::new(0, 0);
+        assert_eq!(origin.manhattan(&Pos::new(-2, 3)), 5);
+        assert_eq!(origin.chebyshev(&Pos::new(-2, 3)), 3);
+        assert_eq!(origin.within_manhattan(2).count(), 13);
+        assert_eq!(origin.within_chebyshev(1).count(), 9);
+        assert!(origin.within_manhattan(3).all(|p| p.manhattan(&origin) <= 3));
+    }
+
+    #[test]
+    fn test_maze_builder() {
+        let maze = Maze::builder().parse("S..#\n.#.#\n...E");
+        assert_eq!((maze.start, maze.end), (Pos::new(0, 0), Pos::new(2, 3)));
+        assert_eq!(maze.shortest_path_cost(), Some(5));
+        assert_eq!(maze.shortest_path().map(|path| path.len()), Some(6));
+
+        let maze = Maze::builder().markers('a', 'b').walls(|c| c == 'x').step_cost(2).parse("a.x\nx.b");
+        assert_eq!(maze.shortest_path_cost(), Some(6));
+
+        let fixed = Maze::builder().start(Pos::new(2, 0)).end(Pos::new(0, 2)).parse("S..#\n.#.#\n...E");
+        assert_eq!(fixed.shortest_path_cost(), Some(4));
+        assert_eq!(fixed.best_path_tiles(), 8);
+
+        // Facing east, reaching the end below takes a turn
+        let turning = Maze::builder().start_direction(Direction::East).turn_cost(10).parse("S.\n.E");
+        assert_eq!(turning.shortest_path_cost(), Some(12));
+        assert_eq!(turning.best_path_tiles(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "no end cell given and no 'E' marker in the maze")]
+    fn test_maze_without_end() {
+        Maze::builder().parse("S..\n...");
+    }
+
+    #[test]
+    #[should_panic(expected = "must be positive")]
+    fn test_maze_zero_cost() {
+        let _ = Maze::builder().turn_cost(0);
+    }
+}
@@ -0,0 +1,215 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::utils::recorder::Frame;
+
+pub type Rgb = [u8; 3];
+
+/// Colors used to paint each character of a text frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    background: Rgb,
+    foreground: Rgb,
+    colors: HashMap<char, Rgb>,
+}
+
+impl Default for Palette {
+    /// Spaces and `.` as a dark background, any other character in white.
+    fn default() -> Self {
+        Palette::new([15, 15, 35], [255, 255, 255])
+            .with(' ', [15, 15, 35])
+            .with('.', [15, 15, 35])
+    }
+}
+
+impl Palette {
+    /// `background` fills the padding of smaller frames, `foreground` every character without a color.
+    pub fn new(background: Rgb, foreground: Rgb) -> Self {
+        Palette { background, foreground, colors: HashMap::new() }
+    }
+
+    pub fn with(mut self, c: char, color: Rgb) -> Self {
+        self.colors.insert(c, color);
+        self
+    }
+
+    pub fn color(&self, c: char) -> Rgb {
+        self.colors.get(&c).copied().unwrap_or(self.foreground)
+    }
+
+    // Background first, then every other color at most once: the GIF color table
+    fn table(&self) -> Vec<Rgb> {
+        let mut table = vec![self.background, self.foreground];
+        let mut colors: Vec<Rgb> = self.colors.values().copied().collect();
+        colors.sort();
+        for color in colors {
+            if !table.contains(&color) {
+                table.push(color);
+            }
+        }
+        table
+    }
+}
+
+/// Turns text frames into images, each character becoming a `scale` x `scale` square.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageExport {
+    palette: Palette,
+    scale: usize,
+    frame_delay: Duration,
+}
+
+impl Default for ImageExport {
+    fn default() -> Self {
+        ImageExport { palette: Palette::default(), scale: 4, frame_delay: Duration::from_millis(100) }
+    }
+}
+
+impl ImageExport {
+    pub fn new(palette: Palette) -> Self {
+        ImageExport { palette, ..Default::default() }
+    }
+
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Time each frame stays on screen in a GIF, rounded to hundredths of a second.
+    pub fn frame_delay(mut self, frame_delay: Duration) -> Self {
+        self.frame_delay = frame_delay;
+        self
+    }
+
+    /// Size in pixels of the image fitting every frame.
+    fn size(&self, frames: &[Frame]) -> (usize, usize) {
+        let columns = frames.iter()
+            .flat_map(|frame| frame.text.lines().map(|line| line.chars().count()))
+            .max()
+            .unwrap_or(0);
+        let rows = frames.iter().map(|frame| frame.text.lines().count()).max().unwrap_or(0);
+
+        (columns.max(1) * self.scale, rows.max(1) * self.scale)
+    }
+
+    /// Indices of the `table` colors for each pixel of the frame, row after row.
+    fn rasterize(&self, frame: &Frame, (width, height): (usize, usize), table: &[Rgb]) -> Vec<u8> {
+        let mut pixels = vec![0; width * height];
+
+        for (row, line) in frame.text.lines().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let color = self.palette.color(c);
+                let index = table.iter().position(|&entry| entry == color).unwrap_or(1) as u8;
+
+                for y in row * self.scale..(row + 1) * self.scale {
+                    pixels[y * width + col * self.scale..y * width + (col + 1) * self.scale].fill(index);
+                }
+            }
+        }
+
+        pixels
+    }
+
+    /// Writes the frames as an animated GIF, looping forever.
+    pub fn write_gif(&self, frames: &[Frame], path: impl AsRef<Path>) -> io::Result<()> {
+        let size @ (width, height) = self.size(frames);
+        let table = self.palette.table();
+        let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(io::Error::other(format!("{width}x{height} pixels is too large for a GIF")));
+        };
+        if table.len() > 256 {
+            return Err(io::Error::other("a GIF can use at most 256 colors"));
+        }
+
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(file, gif_width, gif_height, table.as_flattened())
+            .map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+
+        let delay = (self.frame_delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+        for frame in frames {
+            let gif_frame = gif::Frame {
+                width: gif_width,
+                height: gif_height,
+                delay,
+                buffer: Cow::Owned(self.rasterize(frame, size, &table)),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes one PNG per frame, `out.png` becoming `out-00042.png` for step 42.
+    /// Returns the paths that were written.
+    pub fn write_png_sequence(&self, frames: &[Frame], path: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        let path = path.as_ref();
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("frame");
+        let size @ (width, height) = self.size(frames);
+        let table = self.palette.table();
+
+        let mut written = Vec::new();
+        for frame in frames {
+            let frame_path = path.with_file_name(format!("{stem}-{:05}.png", frame.step));
+            let mut encoder = png::Encoder::new(BufWriter::new(File::create(&frame_path)?), width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+
+            let data: Vec<u8> = self.rasterize(frame, size, &table)
+                .into_iter()
+                .flat_map(|index| table[index as usize])
+                .collect();
+            encoder.write_header()
+                .and_then(|mut writer| writer.write_image_data(&data))
+                .map_err(io::Error::other)?;
+
+            written.push(frame_path);
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rasterize() {
+        let export = ImageExport::new(Palette::default().with('X', [0, 255, 0])).scale(2);
+        let frames = [Frame { step: 0, text: "X.\n.".to_string() }, Frame { step: 1, text: "...".to_string() }];
+        let size = export.size(&frames);
+        assert_eq!(size, (6, 4));
+
+        let table = export.palette.table();
+        let pixels = export.rasterize(&frames[0], size, &table);
+        assert_eq!(table[pixels[0] as usize], [0, 255, 0]);
+        assert_eq!(&pixels[6..12], &pixels[0..6]);
+        assert_eq!(pixels[2..].iter().filter(|&&index| index != 0).count(), 2);
+    }
+
+    #[test]
+    fn test_write_files() {
+        let dir = std::env::temp_dir().join(format!("aoc-image-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let export = ImageExport::new(Palette::default());
+        let frames = [Frame { step: 7, text: "#.".to_string() }, Frame { step: 12, text: ".#".to_string() }];
+
+        let gif = dir.join("out.gif");
+        export.write_gif(&frames, &gif).unwrap();
+        assert!(std::fs::read(&gif).unwrap().starts_with(b"GIF89a"));
+
+        let written = export.write_png_sequence(&frames, dir.join("out.png")).unwrap();
+        assert_eq!(written, vec![dir.join("out-00007.png"), dir.join("out-00012.png")]);
+        for path in &written {
+            assert!(std::fs::read(path).unwrap().starts_with(b"\x89PNG\r\n\x1a\n"));
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,413 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0)` is 0.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as i64
+}
+
+/// Least common multiple, always non-negative. 0 if either number is.
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// Extended Euclid: `(g, x, y)` with `a * x + b * y = g`, `g` being the non-negative gcd.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m`, in `0..m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let m = m.abs();
+    if m == 0 {
+        return None;
+    }
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Chinese remainder theorem: the `(x, m)` such that the numbers satisfying every
+/// `n ≡ residue (mod modulus)` are exactly `x + k * m`, with `x` in `0..m`.
+///
+/// Moduli need not be coprime, the congruences may then have no common solution.
+/// Moduli must be positive.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+
+    for &(residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (residue, modulus) = (residue.rem_euclid(modulus) as i128, modulus as i128);
+
+        // x + m * k ≡ residue (mod modulus), so m * k ≡ residue - x (mod modulus)
+        let (g, inverse, _) = egcd((m % modulus) as i64, modulus as i64);
+        let (g, inverse) = (g as i128, inverse as i128);
+        let difference = residue - x;
+        if difference % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k = (difference / g % step * inverse).rem_euclid(step);
+        x += m * k;
+        m *= step;
+        x = x.rem_euclid(m);
+        if m > i64::MAX as i128 {
+            return None;
+        }
+    }
+
+    Some((x as i64, m as i64))
+}
+
+/// An exact fraction, always stored in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numerator: 0, denominator: 1 };
+    pub const ONE: Rational = Rational { numerator: 1, denominator: 1 };
+
+    /// Panics if `denominator` is 0.
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "zero denominator");
+        let mut g = gcd_i128(numerator, denominator);
+        if denominator < 0 {
+            g = -g;
+        }
+        Rational { numerator: numerator / g, denominator: denominator / g }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value if it is an integer fitting in an `i64`.
+    pub fn to_integer(&self) -> Option<i64> {
+        if self.is_integer() {
+            i64::try_from(self.numerator).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Panics if the rational is 0.
+    pub fn recip(self) -> Self {
+        Rational::new(self.denominator, self.numerator)
+    }
+}
+
+fn gcd_i128(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational { numerator: n as i128, denominator: 1 }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        Rational::new(
+            self.numerator * other.denominator + other.numerator * self.denominator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        Rational::new(self.numerator * other.numerator, self.denominator * other.denominator)
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// Panics when dividing by 0.
+    fn div(self, other: Rational) -> Rational {
+        Rational::new(self.numerator * other.denominator, self.denominator * other.numerator)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational { numerator: -self.numerator, denominator: self.denominator }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+/// Outcome of solving a system of linear equations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearSolution {
+    Unique(Vec<Rational>),
+    /// The equations are dependent. Holds one solution, free variables being set to 0.
+    Infinite(Vec<Rational>),
+    None,
+}
+
+impl LinearSolution {
+    /// The solution if it is unique and made of integers.
+    pub fn integers(&self) -> Option<Vec<i64>> {
+        match self {
+            LinearSolution::Unique(values) => values.iter().map(Rational::to_integer).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Solves `matrix * x = rhs` exactly by Gaussian elimination. `matrix` has one row per
+/// equation and one column per unknown, and may have more or fewer equations than unknowns.
+pub fn solve_linear(matrix: &[Vec<i64>], rhs: &[i64]) -> LinearSolution {
+    assert_eq!(matrix.len(), rhs.len(), "one right-hand side value per equation");
+    let unknowns = matrix.first().map_or(0, Vec::len);
+
+    let mut rows: Vec<Vec<Rational>> = matrix.iter().zip(rhs)
+        .map(|(row, &value)| {
+            assert_eq!(row.len(), unknowns, "every equation has the same unknowns");
+            row.iter().chain([&value]).map(|&n| Rational::from(n)).collect()
+        })
+        .collect();
+
+    // Reduced row echelon form, remembering the column of each pivot
+    let mut pivots = Vec::new();
+    for col in 0..unknowns {
+        let row = pivots.len();
+        let Some(pivot) = (row..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(row, pivot);
+
+        let factor = rows[row][col];
+        rows[row].iter_mut().for_each(|value| *value = *value / factor);
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            let factor = values[col];
+            if other != row && !factor.is_zero() {
+                values.iter_mut().zip(&pivot_row).for_each(|(value, &p)| *value = *value - factor * p);
+            }
+        }
+        pivots.push(col);
+    }
+
+    // A `0 = b` row with `b != 0` left over
+    if rows[pivots.len()..].iter().any(|row| !row[unknowns].is_zero()) {
+        return LinearSolution::None;
+    }
+
+    let mut solution = vec![Rational::ZERO; unknowns];
+    for (row, &col) in pivots.iter().enumerate() {
+        solution[col] = rows[row][unknowns];
+    }
+
+    if pivots.len() == unknowns {
+        LinearSolution::Unique(solution)
+    } else {
+        LinearSolution::Infinite(solution)
+    }
+}
+
+/// Solves `a * x = b` for two unknowns, see `solve_linear`.
+pub fn solve_2x2(a: [[i64; 2]; 2], b: [i64; 2]) -> LinearSolution {
+    solve_linear(&[a[0].to_vec(), a[1].to_vec()], &b)
+}
+
+/// The non-negative integers `(m, n)` with `m * u + n * v = target` minimizing
+/// `m * cost.0 + n * cost.1`, e.g. button presses reaching a prize.
+///
+/// Works when `u` and `v` are collinear, in which case several combinations may reach the
+/// target. Costs are expected to be non-negative.
+pub fn min_cost_combination(u: (i64, i64), v: (i64, i64), target: (i64, i64), cost: (i64, i64)) -> Option<(i64, i64)> {
+    let solution = solve_2x2([[u.0, v.0], [u.1, v.1]], [target.0, target.1]);
+    match solution {
+        LinearSolution::Unique(_) => {
+            let counts = solution.integers()?;
+            (counts[0] >= 0 && counts[1] >= 0).then_some((counts[0], counts[1]))
+        }
+        LinearSolution::None => None,
+        LinearSolution::Infinite(_) => {
+            // Everything lies on one line through the origin: a coordinate where the line does
+            // not vanish determines the other
+            let (p, q, t) = if u.0 != 0 || v.0 != 0 { (u.0, v.0, target.0) } else { (u.1, v.1, target.1) };
+            min_cost_combination_1d(p, q, t, cost)
+        }
+    }
+}
+
+/// The non-negative integers `(m, n)` with `m * p + n * q = t` minimizing `m * cost.0 + n * cost.1`.
+fn min_cost_combination_1d(p: i64, q: i64, t: i64, cost: (i64, i64)) -> Option<(i64, i64)> {
+    let (p, q, t) = (p as i128, q as i128, t as i128);
+    let (cost_m, cost_n) = (cost.0 as i128, cost.1 as i128);
+
+    if p == 0 && q == 0 {
+        return (t == 0).then_some((0, 0));
+    }
+    let (g, x, y) = egcd(p as i64, q as i64);
+    let g = g as i128;
+    if t % g != 0 {
+        return None;
+    }
+
+    // Every solution is (m0 + k * dm, n0 - k * dn), a free count staying at 0 when its step is 0
+    let (m0, n0) = (x as i128 * (t / g), y as i128 * (t / g));
+    let (dm, dn) = (q / g, p / g);
+
+    // Range of k keeping both counts non-negative
+    let mut low: Option<i128> = None;
+    let mut high: Option<i128> = None;
+    let mut bound = |value: i128, step: i128| -> bool {
+        // value + k * step >= 0
+        match step.cmp(&0) {
+            Ordering::Greater => low = Some(low.map_or(div_ceil(-value, step), |l| l.max(div_ceil(-value, step)))),
+            Ordering::Less => high = Some(high.map_or(div_floor(value, -step), |h| h.min(div_floor(value, -step)))),
+            Ordering::Equal => return value >= 0,
+        }
+        true
+    };
+    if !bound(m0, dm) || !bound(n0, -dn) {
+        return None;
+    }
+
+    let slope = cost_m * dm - cost_n * dn;
+    let k = match (low, high) {
+        (Some(l), Some(h)) if l > h => return None,
+        (Some(l), Some(h)) => if slope > 0 { l } else if slope < 0 { h } else { l.min(h) },
+        (Some(l), None) if slope >= 0 => l,
+        (None, Some(h)) if slope <= 0 => h,
+        // Unbounded, only possible with negative costs
+        _ => return None,
+    };
+
+    let (m, n) = (m0 + k * dm, n0 - k * dn);
+    Some((i64::try_from(m).ok()?, i64::try_from(n).ok()?))
+}
+
+// Rounded divisions by a positive `b`
+fn div_floor(a: i128, b: i128) -> i128 {
+    a.div_euclid(b)
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -(-a).div_euclid(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!((gcd(-12, 18), lcm(4, -6), gcd(0, 0)), (6, 12, 0));
+        let (g, x, y) = egcd(240, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(4, 8), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    }
+
+    #[test]
+    fn test_solve_linear() {
+        let half = Rational::new(1, 2);
+        assert_eq!(solve_2x2([[2, 0], [0, 4]], [1, 2]), LinearSolution::Unique(vec![half, half]));
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [1, 3]), LinearSolution::None);
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 6]), LinearSolution::Infinite(vec![3.into(), 0.into()]));
+
+        let matrix = [vec![1, 1, 1], vec![0, 2, 5], vec![2, 5, -1]];
+        assert_eq!(solve_linear(&matrix, &[6, -4, 27]).integers(), Some(vec![5, 3, -2]));
+    }
+
+    #[test]
+    fn test_min_cost_combination() {
+        assert_eq!(min_cost_combination((94, 34), (22, 67), (8400, 5400), (3, 1)), Some((80, 40)));
+        assert_eq!(min_cost_combination((26, 66), (67, 21), (12748, 12176), (3, 1)), None);
+        // Collinear buttons: 3 * A + 1 * B or 0 * A + 4 * B reach (12, 12)
+        assert_eq!(min_cost_combination((3, 3), (3, 3), (12, 12), (3, 1)), Some((0, 4)));
+        assert_eq!(min_cost_combination((2, 2), (3, 3), (7, 7), (1, 5)), Some((2, 1)));
+        assert_eq!(min_cost_combination((2, 4), (3, 6), (7, 15), (1, 1)), None);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// Number of lookups answered from the cache, or computed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.hits + self.misses;
+        let rate = if total == 0 { 0.0 } else { 100.0 * self.hits as f64 / total as f64 };
+        write!(f, "{} hits, {} misses ({rate:.1}% hit rate)", self.hits, self.misses)
+    }
+}
+
+/// Cache of a pure function, by argument.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: MemoStats,
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo { cache: HashMap::new(), stats: MemoStats::default() }
+    }
+
+    /// The cached value for `key`, or the one computed by `compute` and cached. `compute` gets
+    /// the memo back to look up other keys, e.g. from a recursive function.
+    pub fn get(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Forgets every value and resets the statistics, e.g. before solving another input.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = MemoStats::default();
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+/// A recursive function caching its results. It receives itself as its first argument to make
+/// the recursive calls, e.g.
+/// `Memoized::new(|fib: &mut dyn FnMut(u64) -> u64, n| if n < 2 { n } else { fib(n - 1) + fib(n - 2) })`.
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    function: F,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(function: F) -> Self {
+        Memoized { memo: Memo::new(), function }
+    }
+
+    pub fn call(&mut self, key: K) -> V {
+        call_memoized(&mut self.memo, &self.function, key)
+    }
+
+    pub fn memo(&self) -> &Memo<K, V> {
+        &self.memo
+    }
+
+    /// See `Memo::clear`.
+    pub fn clear(&mut self) {
+        self.memo.clear();
+    }
+}
+
+fn call_memoized<K, V, F>(memo: &mut Memo<K, V>, function: &F, key: K) -> V
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    memo.get(key.clone(), |memo| function(&mut |key| call_memoized(memo, function, key), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memoized() {
+        let mut fib = Memoized::new(|fib: &mut dyn FnMut(u64) -> u64, n| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+        assert_eq!(fib.call(90), 2880067194370816120);
+        assert_eq!(fib.memo().stats(), MemoStats { hits: 88, misses: 91 });
+
+        fib.clear();
+        assert!(fib.memo().is_empty());
+        assert_eq!(fib.call(10), 55);
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(memo.get("a", |_| 1), 1);
+        assert_eq!(memo.get("a", |_| 2), 1);
+        assert_eq!(memo.get("b", |memo| memo.get("a", |_| 3) + 1), 2);
+        assert_eq!(memo.stats().to_string(), "2 hits, 2 misses (50.0% hit rate)");
+    }
+}
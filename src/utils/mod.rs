@@ -0,0 +1,19 @@
+pub mod bitset;
+pub mod circuit;
+pub mod cycle;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod grid_utils;
+pub mod image;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod ranges;
+pub mod recorder;
+pub mod region;
+pub mod render;
+pub mod search;
+pub mod simulation;
+pub mod union_find;
+pub mod vm;
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use itertools::traits::HomogeneousTuple;
+use itertools::Itertools;
+
+/// Invalid puzzle input, located by its line and column, both starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about the character at byte `offset` of `text`.
+    pub fn at(text: &str, offset: usize, message: impl Into<String>) -> Self {
+        let before = &text[..offset.min(text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// The same error, for a text starting at the beginning of a line, `lines` lines further in the input.
+    pub fn shifted(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Every integer in the text with its byte offset, a `-` right before the digits making it negative.
+/// An integer that does not fit in an `i64` is an error.
+fn ints_with_offsets(text: &str) -> impl Iterator<Item = Result<(usize, i64), ParseError>> + '_ {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() && !bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return None;
+        }
+
+        let negative = i > 0 && bytes[i - 1] == b'-';
+        let start = if negative { i - 1 } else { i };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        // Only digits and a sign, so the sole failure is an overflow
+        let number = &text[start..i];
+        Some(number.parse()
+            .map(|value| (start, value))
+            .map_err(|_| ParseError::at(text, start, format!("{number} does not fit in a 64-bit integer"))))
+    })
+}
+
+/// Every integer in the text, ignoring whatever separates them, e.g. `p=0,4 v=3,-3` gives 0, 4, 3 and -3.
+pub fn ints(text: &str) -> Result<Vec<i64>, ParseError> {
+    ints_with_offsets(text).map(|found| found.map(|(_, value)| value)).collect()
+}
+
+/// Exactly `N` integers of the text, e.g. `let [x, y] = ints_array(line)?`.
+pub fn ints_array<const N: usize>(text: &str) -> Result<[i64; N], ParseError> {
+    let found: Vec<(usize, i64)> = ints_with_offsets(text).take(N + 1).collect::<Result<_, _>>()?;
+
+    match found.len() {
+        count if count < N => Err(ParseError::at(text, text.len(), format!("expected {N} integers, found {count}"))),
+        count if count > N => Err(ParseError::at(text, found[N].0, format!("expected {N} integers, found more"))),
+        _ => Ok(std::array::from_fn(|i| found[i].1)),
+    }
+}
+
+/// Exactly as many integers as the tuple holds, e.g. `let (x, y, z) = ints_tuple(line)?`.
+pub fn ints_tuple<T: HomogeneousTuple<Item = i64>>(text: &str) -> Result<T, ParseError> {
+    let arity = T::num_items();
+    let found: Vec<(usize, i64)> = ints_with_offsets(text).take(arity + 1).collect::<Result<_, _>>()?;
+
+    match found.len() {
+        count if count < arity => Err(ParseError::at(text, text.len(), format!("expected {arity} integers, found {count}"))),
+        count if count > arity => Err(ParseError::at(text, found[arity].0, format!("expected {arity} integers, found more"))),
+        _ => Ok(found.into_iter().map(|(_, value)| value).collect_tuple().unwrap()),
+    }
+}
+
+/// Part of an input separated from the others by blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub text: &'a str,
+    /// Number of input lines before the section.
+    pub line_offset: usize,
+}
+
+impl<'a> Section<'a> {
+    /// Applies `parse` to the section text, locating its errors in the whole input.
+    pub fn parse<T>(&self, parse: impl FnOnce(&'a str) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        parse(self.text).map_err(|e| e.shifted(self.line_offset))
+    }
+}
+
+/// Splits the input on blank lines.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    sections_with_offsets(input).into_iter().map(|(_, section)| section).collect()
+}
+
+// Sections along with the byte offset where they start
+fn sections_with_offsets(input: &str) -> Vec<(usize, Section<'_>)> {
+    let mut sections = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for (index, line) in input.split_inclusive('\n').enumerate() {
+        let is_blank = line.trim().is_empty();
+        match (start, is_blank) {
+            (None, false) => start = Some((index, offset)),
+            (Some((line_offset, from)), true) => {
+                sections.push((from, Section { text: input[from..end].trim_end(), line_offset }));
+                start = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+        if !is_blank {
+            end = offset;
+        }
+    }
+    if let Some((line_offset, from)) = start {
+        sections.push((from, Section { text: input[from..end].trim_end(), line_offset }));
+    }
+
+    sections
+}
+
+/// Exactly `N` sections, e.g. `let [rules, updates] = sections_array(input)?`.
+pub fn sections_array<const N: usize>(input: &str) -> Result<[Section<'_>; N], ParseError> {
+    let found = sections_with_offsets(input);
+    if found.len() != N {
+        let offset = found.get(N).map_or(input.len(), |&(offset, _)| offset);
+        let message = format!("expected {N} sections separated by blank lines, found {}", found.len());
+        return Err(ParseError::at(input, offset, message));
+    }
+
+    Ok(std::array::from_fn(|i| found[i].1))
+}
+
+/// `key: value` lines, e.g. `Register A: 729`, the value being trimmed.
+pub fn key_values(text: &str) -> Result<HashMap<&str, &str>, ParseError> {
+    let mut map = HashMap::new();
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(ParseError::at(text, offset, "expected a `key: value` line"));
+            };
+            if map.insert(key.trim(), value.trim()).is_some() {
+                return Err(ParseError::at(text, offset, format!("duplicate key `{}`", key.trim())));
+            }
+        }
+        offset += line.len();
+    }
+
+    Ok(map)
+}
+
+/// Comma separated values, spaces around them being ignored, e.g. `r, wr, b` or `0,1,5,4`.
+pub fn comma_list<T>(text: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut offset = 0;
+
+    text.split(',')
+        .map(|item| {
+            let item_offset = offset + (item.len() - item.trim_start().len());
+            offset += item.len() + 1;
+
+            item.trim().parse().map_err(|e| ParseError::at(text, item_offset, format!("invalid value `{}`: {e}", item.trim())))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(ints("p=0,4 v=3,-3"), Ok(vec![0, 4, 3, -3]));
+        assert_eq!(ints_array::<2>("X+94, Y+34"), Ok([94, 34]));
+        assert_eq!(ints_tuple::<(i64, i64)>("1 2 3").unwrap_err().column, 5);
+        assert_eq!(ints_array::<3>("1 2").unwrap_err().message, "expected 3 integers, found 2");
+
+        assert_eq!(ints("-9223372036854775808"), Ok(vec![i64::MIN]));
+        let overflow = ints_array::<2>("a: 1\nb: -99999999999999999999").unwrap_err();
+        assert_eq!((overflow.line, overflow.column), (2, 4));
+        assert_eq!(overflow.message, "-99999999999999999999 does not fit in a 64-bit integer");
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "a: 1\nb: 2\n\n\nc\nd\n";
+        let [registers, rest] = sections_array(input).unwrap();
+        assert_eq!(registers.text, "a: 1\nb: 2");
+        assert_eq!((rest.text, rest.line_offset), ("c\nd", 4));
+        assert_eq!(registers.parse(key_values).unwrap()["b"], "2");
+
+        let error = rest.parse(key_values).unwrap_err();
+        assert_eq!((error.line, error.column), (5, 1));
+        assert_eq!(error.to_string(), "line 5, column 1: expected a `key: value` line");
+    }
+
+    #[test]
+    fn test_comma_list() {
+        assert_eq!(comma_list::<String>("r, wr, b").unwrap(), vec!["r", "wr", "b"]);
+        assert_eq!(comma_list::<u8>("0,1, x").unwrap_err().column, 6);
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::ops::{Add, Range, Sub};
+
+/// A set of values stored as disjoint half-open ranges, adjacent ranges being merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    // Start to end of each range
+    ranges: BTreeMap<T, T>,
+}
+
+impl<T: Copy + Ord> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet::new()
+    }
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    pub fn new() -> Self {
+        RangeSet { ranges: BTreeMap::new() }
+    }
+
+    /// Adds the values of `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let (mut start, mut end) = (range.start, range.end);
+        let touching: Vec<(T, T)> = self.ranges.range(..=end)
+            .rev()
+            .take_while(|&(_, &e)| e >= start)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in touching {
+            self.ranges.remove(&s);
+            start = start.min(s);
+            end = end.max(e);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    /// Removes the values of `range`, splitting the ranges it cuts through.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let overlapping: Vec<(T, T)> = self.ranges.range(..range.end)
+            .rev()
+            .take_while(|&(_, &e)| e > range.start)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in overlapping {
+            self.ranges.remove(&s);
+            if s < range.start {
+                self.ranges.insert(s, range.start);
+            }
+            if e > range.end {
+                self.ranges.insert(range.end, e);
+            }
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.range_of(value).is_some()
+    }
+
+    /// The range holding `value`.
+    pub fn range_of(&self, value: T) -> Option<Range<T>> {
+        self.ranges.range(..=value)
+            .next_back()
+            .filter(|&(_, &end)| value < end)
+            .map(|(&start, &end)| start..end)
+    }
+
+    /// Whether every value of `range` is in the set.
+    pub fn contains_range(&self, range: Range<T>) -> bool {
+        range.is_empty() || self.range_of(range.start).is_some_and(|found| range.end <= found.end)
+    }
+
+    /// Every value of either set.
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut union = self.clone();
+        union.extend(other.iter());
+        union
+    }
+
+    /// The ranges in increasing order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Range<T>> + '_ {
+        self.ranges.iter().map(|(&start, &end)| start..end)
+    }
+
+    /// Number of ranges, rather than of values, see `len`.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+impl<T> RangeSet<T>
+where
+    T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Number of values in the set.
+    pub fn len(&self) -> T {
+        self.ranges.iter().fold(T::default(), |total, (&start, &end)| total + (end - start))
+    }
+
+    /// The values within `bounds` that are not in the set.
+    pub fn gaps(&self, bounds: Range<T>) -> RangeSet<T> {
+        let mut gaps = RangeSet::new();
+        gaps.insert(bounds);
+        for range in self.iter() {
+            gaps.remove(range);
+        }
+        gaps
+    }
+
+    /// First-fit allocation: takes `size` values off the start of the first range long enough,
+    /// provided they all come before `limit`.
+    pub fn first_fit(&mut self, size: T, limit: T) -> Option<Range<T>> {
+        let start = self.ranges.iter()
+            .take_while(|&(&start, _)| start + size <= limit)
+            .find(|&(&start, &end)| end - start >= size)
+            .map(|(&start, _)| start)?;
+
+        self.remove(start..start + size);
+        Some(start..start + size)
+    }
+}
+
+impl<T: Copy + Ord> Extend<Range<T>> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, ranges: I) {
+        ranges.into_iter().for_each(|range| self.insert(range));
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> Self {
+        let mut set = RangeSet::new();
+        set.extend(ranges);
+        set
+    }
+}
+
+/// Values attached to disjoint half-open ranges, adjacent ranges holding equal values being merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalMap<T, V> {
+    // Start to end and value of each interval
+    intervals: BTreeMap<T, (T, V)>,
+}
+
+impl<T: Copy + Ord, V: Clone + PartialEq> Default for IntervalMap<T, V> {
+    fn default() -> Self {
+        IntervalMap::new()
+    }
+}
+
+impl<T: Copy + Ord, V: Clone + PartialEq> IntervalMap<T, V> {
+    pub fn new() -> Self {
+        IntervalMap { intervals: BTreeMap::new() }
+    }
+
+    /// Sets the value of `range`, replacing whatever the map held there.
+    pub fn insert(&mut self, range: Range<T>, value: V) {
+        if range.is_empty() {
+            return;
+        }
+        self.remove(range.clone());
+
+        // Merge with the neighbours holding the same value
+        let (mut start, mut end) = (range.start, range.end);
+        if let Some((&before, (before_end, before_value))) = self.intervals.range(..start).next_back() {
+            if *before_end == start && *before_value == value {
+                self.intervals.remove(&before);
+                start = before;
+            }
+        }
+        if let Some(&(after_end, ref after_value)) = self.intervals.get(&end) {
+            if *after_value == value {
+                self.intervals.remove(&end);
+                end = after_end;
+            }
+        }
+
+        self.intervals.insert(start, (end, value));
+    }
+
+    /// Clears `range`, splitting the intervals it cuts through.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let overlapping: Vec<T> = self.intervals.range(..range.end)
+            .rev()
+            .take_while(|(_, (e, _))| *e > range.start)
+            .map(|(&s, _)| s)
+            .collect();
+        for s in overlapping {
+            let (e, value) = self.intervals.remove(&s).unwrap();
+            if s < range.start {
+                self.intervals.insert(s, (range.start, value.clone()));
+            }
+            if e > range.end {
+                self.intervals.insert(range.end, (e, value));
+            }
+        }
+    }
+
+    pub fn get(&self, point: T) -> Option<&V> {
+        self.interval_of(point).map(|(_, value)| value)
+    }
+
+    /// The interval holding `point`, with its value.
+    pub fn interval_of(&self, point: T) -> Option<(Range<T>, &V)> {
+        self.intervals.range(..=point)
+            .next_back()
+            .filter(|(_, (end, _))| point < *end)
+            .map(|(&start, (end, value))| (start..*end, value))
+    }
+
+    /// The intervals in increasing order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Range<T>, &V)> + '_ {
+        self.intervals.iter().map(|(&start, (end, value))| (start..*end, value))
+    }
+
+    /// Number of intervals.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_set() {
+        let mut set: RangeSet<i64> = [0..3, 5..8, 3..4].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..4, 5..8]);
+        assert_eq!(set.len(), 7);
+
+        set.remove(2..6);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..2, 6..8]);
+        assert!(set.contains(7) && !set.contains(8));
+        assert!(set.contains_range(6..8) && !set.contains_range(1..7));
+        assert_eq!(set.gaps(-1..10).iter().collect::<Vec<_>>(), vec![-1..0, 2..6, 8..10]);
+    }
+
+    #[test]
+    fn test_first_fit() {
+        let mut free: RangeSet<usize> = [2..5, 8..11, 12..18].into_iter().collect();
+        assert_eq!(free.first_fit(4, 20), Some(12..16));
+        assert_eq!(free.first_fit(2, 10), Some(2..4));
+        assert_eq!(free.first_fit(3, 10), None);
+        assert_eq!(free.range_count(), 3);
+    }
+
+    #[test]
+    fn test_interval_map() {
+        let mut map = IntervalMap::new();
+        map.insert(0..10, 'a');
+        map.insert(3..5, 'b');
+        assert_eq!(map.get(4), Some(&'b'));
+        assert_eq!(map.interval_of(7), Some((5..10, &'a')));
+
+        map.insert(3..5, 'a');
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(0..10, &'a')]);
+        map.remove(2..4);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(3), None);
+    }
+}
@@ -0,0 +1,308 @@
+use std::fmt::Display;
+use std::io::{self, stdin, stdout, BufRead, Write};
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use std::{fs, thread};
+
+use crate::utils::image::ImageExport;
+
+/// Text rendering of a simulation at a given step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub step: usize,
+    pub text: String,
+}
+
+/// Collects the frames of a step simulation to replay them afterwards.
+///
+/// Frames are expected to be plain text, e.g. a `Render` built with `plain()`, and must be
+/// recorded with increasing step numbers.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    frames: Vec<Frame>,
+    every: usize,
+    images: ImageExport,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Recorder::new()
+    }
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Recorder { frames: Vec::new(), every: 1, images: ImageExport::default() }
+    }
+
+    /// Only keeps the steps that are a multiple of `every`, for simulations running for long.
+    pub fn sampling(every: usize) -> Self {
+        Recorder { frames: Vec::new(), every: every.max(1), images: ImageExport::default() }
+    }
+
+    /// Palette, scale and speed used by `export_images`.
+    pub fn with_images(mut self, images: ImageExport) -> Self {
+        self.images = images;
+        self
+    }
+
+    pub fn record(&mut self, step: usize, frame: impl Display) {
+        if step.is_multiple_of(self.every) {
+            self.frames.push(Frame { step, text: frame.to_string() });
+        }
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Index of the last frame recorded at or before `step`, or of the first frame if none was.
+    fn index_at(&self, step: usize) -> usize {
+        self.frames.partition_point(|frame| frame.step <= step).saturating_sub(1)
+    }
+
+    /// The frame showing the simulation at `step`, i.e. the last one recorded up to then.
+    pub fn seek(&self, step: usize) -> Option<&Frame> {
+        self.frames.get(self.index_at(step)).filter(|frame| frame.step <= step)
+    }
+
+    /// Frames whose step is within `steps`.
+    pub fn select(&self, steps: impl RangeBounds<usize>) -> impl Iterator<Item = &Frame> {
+        self.frames.iter().filter(move |frame| steps.contains(&frame.step))
+    }
+
+    /// Writes the frames whose step is within `steps` to a text file, each under a step header.
+    pub fn dump(&self, path: impl AsRef<Path>, steps: impl RangeBounds<usize>) -> io::Result<()> {
+        let text: String = self.select(steps)
+            .map(|frame| format!("Step {}\n{}\n\n", frame.step, frame.text))
+            .collect();
+
+        fs::write(path, text)
+    }
+
+    /// Writes the frames whose step is within `steps` as an animated GIF if `path` ends with
+    /// `.gif`, or as a PNG sequence numbered by step otherwise. Returns the number of frames written.
+    pub fn export_images(&self, path: impl AsRef<Path>, steps: impl RangeBounds<usize>) -> io::Result<usize> {
+        let frames: Vec<Frame> = self.select(steps).cloned().collect();
+        let is_gif = path.as_ref().extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+
+        if is_gif {
+            self.images.write_gif(&frames, path)?;
+        } else {
+            self.images.write_png_sequence(&frames, path)?;
+        }
+
+        Ok(frames.len())
+    }
+
+    /// Plays the frames in the terminal, reading commands from the standard input.
+    ///
+    /// Commands are followed by Enter: an empty line or `p` pauses and resumes, `n` and `b` move
+    /// one frame forward or backward, `g <step>` goes to a step, `+` and `-` change the speed and
+    /// `q` quits. Playback pauses on the last frame.
+    pub fn play(&self, frames_per_second: f64) {
+        if self.frames.is_empty() {
+            return;
+        }
+
+        let (sender, commands) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdin().lock().lines().map_while(Result::ok) {
+                if let Ok(command) = line.parse::<Command>() {
+                    if sender.send(command).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut playback = Playback::new(frames_per_second);
+        loop {
+            self.draw(&playback);
+
+            let command = if playback.paused {
+                commands.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                commands.recv_timeout(playback.delay)
+            };
+
+            match command {
+                Ok(Command::Quit) => break,
+                Ok(command) => playback.apply(command, self),
+                Err(RecvTimeoutError::Timeout) => playback.tick(self),
+                // Without a terminal to read from, play once until the end
+                Err(RecvTimeoutError::Disconnected) if playback.paused => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(playback.delay);
+                    playback.tick(self);
+                }
+            }
+        }
+    }
+
+    fn draw(&self, playback: &Playback) {
+        let frame = &self.frames[playback.index];
+        let state = if playback.paused { "paused" } else { "playing" };
+
+        let mut stdout = stdout();
+        let _ = write!(
+            stdout,
+            "\x1b[2J\x1b[H{}\n\nStep {} (frame {}/{}), {state} at {:.1} fps. [Enter] pause, n/b step, g <step>, +/- speed, q quit\n",
+            frame.text,
+            frame.step,
+            playback.index + 1,
+            self.frames.len(),
+            1.0 / playback.delay.as_secs_f64(),
+        );
+        let _ = stdout.flush();
+    }
+}
+
+/// Parses a selection of steps written `5`, `5..10`, `5..=10`, `5..` or `..10`.
+pub fn parse_steps(s: &str) -> Option<(Bound<usize>, Bound<usize>)> {
+    let Some((start, end)) = s.split_once("..") else {
+        let step = s.trim().parse().ok()?;
+        return Some((Bound::Included(step), Bound::Included(step)));
+    };
+
+    let start = match start.trim() {
+        "" => Bound::Unbounded,
+        start => Bound::Included(start.parse().ok()?),
+    };
+    let end = match end.trim() {
+        "" => Bound::Unbounded,
+        end => match end.strip_prefix('=') {
+            Some(end) => Bound::Included(end.trim().parse().ok()?),
+            None => Bound::Excluded(end.parse().ok()?),
+        },
+    };
+
+    Some((start, end))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    TogglePause,
+    Next,
+    Previous,
+    Seek(usize),
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" | "p" => Ok(Command::TogglePause),
+            "n" => Ok(Command::Next),
+            "b" => Ok(Command::Previous),
+            "+" => Ok(Command::Faster),
+            "-" => Ok(Command::Slower),
+            "q" => Ok(Command::Quit),
+            other => other.strip_prefix('g')
+                .and_then(|step| step.trim().parse().ok())
+                .map(Command::Seek)
+                .ok_or_else(|| format!("Unknown playback command: {other}")),
+        }
+    }
+}
+
+// Fastest playback, about a thousand frames per second
+const MIN_DELAY: Duration = Duration::from_millis(1);
+
+struct Playback {
+    index: usize,
+    paused: bool,
+    delay: Duration,
+}
+
+impl Playback {
+    fn new(frames_per_second: f64) -> Self {
+        let delay = Duration::from_secs_f64(1.0 / frames_per_second.max(0.1)).max(MIN_DELAY);
+        Playback { index: 0, paused: false, delay }
+    }
+
+    fn tick(&mut self, recorder: &Recorder) {
+        if self.index + 1 < recorder.len() {
+            self.index += 1;
+        } else {
+            self.paused = true;
+        }
+    }
+
+    fn apply(&mut self, command: Command, recorder: &Recorder) {
+        match command {
+            Command::TogglePause => self.paused = !self.paused,
+            Command::Next => {
+                self.paused = true;
+                self.index = (self.index + 1).min(recorder.len() - 1);
+            }
+            Command::Previous => {
+                self.paused = true;
+                self.index = self.index.saturating_sub(1);
+            }
+            Command::Seek(step) => {
+                self.paused = true;
+                self.index = recorder.index_at(step);
+            }
+            Command::Faster => self.delay = (self.delay / 2).max(MIN_DELAY),
+            Command::Slower => self.delay *= 2,
+            Command::Quit => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorder() -> Recorder {
+        let mut recorder = Recorder::sampling(2);
+        (0..10).for_each(|step| recorder.record(step, format!("frame {step}")));
+        recorder
+    }
+
+    #[test]
+    fn test_recorder() {
+        let recorder = recorder();
+        assert_eq!(recorder.len(), 5);
+        assert_eq!(recorder.seek(5).map(|frame| frame.step), Some(4));
+        assert_eq!(recorder.select(parse_steps("3..=6").unwrap()).count(), 2);
+        assert_eq!(parse_steps("..4"), Some((Bound::Unbounded, Bound::Excluded(4))));
+    }
+
+    #[test]
+    fn test_playback_commands() {
+        let recorder = recorder();
+        let mut playback = Playback::new(10.0);
+
+        playback.apply("g 7".parse().unwrap(), &recorder);
+        assert_eq!((playback.index, playback.paused), (3, true));
+        playback.apply("n".parse().unwrap(), &recorder);
+        playback.apply("n".parse().unwrap(), &recorder);
+        assert_eq!(playback.index, 4);
+        playback.apply("".parse().unwrap(), &recorder);
+        assert!(!playback.paused);
+        playback.tick(&recorder);
+        assert!(playback.paused);
+        assert!("x".parse::<Command>().is_err());
+
+        (0..20).for_each(|_| playback.apply(Command::Faster, &recorder));
+        assert_eq!(playback.delay, MIN_DELAY);
+        assert_eq!(Playback::new(1e9).delay, MIN_DELAY);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::utils::bitset::GridBitSet;
+use crate::utils::grid_utils::{get_value_from_grid_pos, Direction, Pos, CARDINAL_DIRECTIONS};
+
+/// A set of cells connected through their cardinal neighbors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub cells: HashSet<Pos>,
+}
+
+impl Region {
+    pub fn contains(&self, pos: &Pos) -> bool {
+        self.cells.contains(pos)
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Number of cell sides touching a cell outside the region, holes included.
+    pub fn perimeter(&self) -> usize {
+        self.cells.iter()
+            .flat_map(|cell| Direction::cardinal().map(move |direction| cell.neighbor(direction)))
+            .filter(|neighbor| !self.contains(neighbor))
+            .count()
+    }
+
+    /// Number of corners of the region outline, holes included.
+    pub fn corners(&self) -> usize {
+        let mut corners = 0;
+
+        for cell in &self.cells {
+            for direction in Direction::cardinal() {
+                // Check the corner between this side and the next one clockwise
+                let side = self.contains(&cell.neighbor(direction));
+                let next_side = self.contains(&cell.neighbor(direction.turn_right()));
+                let diagonal = self.contains(&(*cell + direction.offset() + direction.turn_right().offset()));
+
+                let is_outer_corner = !side && !next_side;
+                let is_inner_corner = side && next_side && !diagonal;
+                if is_outer_corner || is_inner_corner {
+                    corners += 1;
+                }
+            }
+        }
+
+        corners
+    }
+
+    /// Number of straight sides of the outline, which is always its number of corners.
+    pub fn sides(&self) -> usize {
+        self.corners()
+    }
+
+    /// Top-left and bottom-right corners of the smallest rectangle holding the region.
+    pub fn bounding_box(&self) -> Option<(Pos, Pos)> {
+        let min_row = self.cells.iter().map(|cell| cell.row).min()?;
+        let max_row = self.cells.iter().map(|cell| cell.row).max()?;
+        let min_col = self.cells.iter().map(|cell| cell.col).min()?;
+        let max_col = self.cells.iter().map(|cell| cell.col).max()?;
+
+        Some((Pos::new(min_row, min_col), Pos::new(max_row, max_col)))
+    }
+
+    /// Cells of the region having at least one cardinal neighbor outside of it.
+    pub fn boundary(&self) -> Vec<Pos> {
+        self.cells.iter()
+            .filter(|cell| Direction::cardinal().any(|direction| !self.contains(&cell.neighbor(direction))))
+            .copied()
+            .collect()
+    }
+}
+
+/// Splits the grid into regions of neighboring cells holding the same value.
+pub fn label_regions<T: PartialEq>(grid: &[Vec<T>]) -> Vec<Region> {
+    regions_by(grid, |_| true, |a, b| a == b)
+}
+
+/// Regions made of the cells matching `predicate`, e.g. every open area of a maze.
+pub fn regions_matching<T>(grid: &[Vec<T>], predicate: impl Fn(&T) -> bool) -> Vec<Region> {
+    regions_by(grid, predicate, |_, _| true)
+}
+
+/// Flood fills the cells matching `predicate`, two neighbors being in the same region
+/// when `connects` accepts their values.
+pub fn regions_by<T>(
+    grid: &[Vec<T>],
+    predicate: impl Fn(&T) -> bool,
+    connects: impl Fn(&T, &T) -> bool,
+) -> Vec<Region> {
+    let mut visited = GridBitSet::for_grid(grid);
+    let mut regions = Vec::new();
+
+    for (row, line) in grid.iter().enumerate() {
+        for (col, value) in line.iter().enumerate() {
+            let start = Pos::new(row as isize, col as isize);
+            if visited.contains(&start) || !predicate(value) {
+                continue;
+            }
+
+            visited.insert(start);
+            let mut cells = HashSet::new();
+            let mut queue = VecDeque::from([start]);
+
+            while let Some(cell) = queue.pop_front() {
+                let cell_value = &grid[cell.row as usize][cell.col as usize];
+                cells.insert(cell);
+
+                for neighbor in cell.neighbors(&CARDINAL_DIRECTIONS) {
+                    let Some(neighbor_value) = get_value_from_grid_pos(grid, neighbor) else {
+                        continue;
+                    };
+                    if !visited.contains(&neighbor) && predicate(neighbor_value) && connects(cell_value, neighbor_value) {
+                        visited.insert(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+
+            regions.push(Region { cells });
+        }
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::grid_utils::parse_grid;
+
+    #[test]
+    fn test_regions() {
+        let grid = parse_grid("AAAA\nBBCD\nBBCC\nEEEC");
+        let regions = label_regions(&grid);
+        assert_eq!(regions.len(), 5);
+
+        let c = regions.iter().find(|r| r.contains(&Pos::new(1, 2))).unwrap();
+        assert_eq!((c.area(), c.perimeter(), c.sides()), (4, 10, 8));
+        assert_eq!(c.bounding_box(), Some((Pos::new(1, 2), Pos::new(3, 3))));
+
+        // A region with a hole has its inner outline counted too
+        let ring = regions_matching(&parse_grid("OOO\nO.O\nOOO"), |&c| c == 'O');
+        assert_eq!((ring[0].area(), ring[0].perimeter(), ring[0].sides(), ring[0].boundary().len()), (8, 16, 8, 8));
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::utils::grid::GridLike;
+use crate::utils::grid_utils::{Direction, Pos};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::Gray => 90,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    glyph: char,
+    color: Option<Color>,
+}
+
+type CellToChar<'a, C> = Box<dyn Fn(Option<&C>) -> char + 'a>;
+
+/// Text rendering of a grid, with overlays drawn on top of its cells.
+///
+/// Overlays are applied in the order they are added, so a later one hides an earlier one.
+/// Colors are written as ANSI escape codes, and left out entirely by `plain`.
+pub struct Render<'a, G: GridLike> {
+    grid: &'a G,
+    to_char: CellToChar<'a, G::Cell>,
+    overlays: HashMap<Pos, Cell>,
+    viewport: Option<(Pos, Pos)>,
+    colors: bool,
+}
+
+impl<'a, G: GridLike> Render<'a, G> {
+    /// `to_char` gets `None` for the cells a sparse grid lacks.
+    pub fn new(grid: &'a G, to_char: impl Fn(Option<&G::Cell>) -> char + 'a) -> Self {
+        Render { grid, to_char: Box::new(to_char), overlays: HashMap::new(), viewport: None, colors: true }
+    }
+
+    /// Draws `glyph` on every position.
+    pub fn points(mut self, positions: impl IntoIterator<Item = Pos>, glyph: char, color: Option<Color>) -> Self {
+        for pos in positions {
+            self.overlays.insert(pos, Cell { glyph, color });
+        }
+        self
+    }
+
+    pub fn marker(self, pos: Pos, glyph: char, color: Option<Color>) -> Self {
+        self.points([pos], glyph, color)
+    }
+
+    /// Draws each step of the path as an arrow pointing to the next position, as printed by
+    /// `Direction::from_char`. Steps that are not between adjacent cells are drawn as `*`.
+    pub fn path(mut self, path: &[Pos], color: Option<Color>) -> Self {
+        for step in path.windows(2) {
+            let glyph = Direction::cardinal()
+                .find(|&direction| step[0].neighbor(direction) == step[1])
+                .map_or('*', |direction| match direction {
+                    Direction::North => '^',
+                    Direction::East => '>',
+                    Direction::South => 'v',
+                    _ => '<',
+                });
+            self.overlays.insert(step[0], Cell { glyph, color });
+        }
+        if let Some(&last) = path.last() {
+            self.overlays.insert(last, Cell { glyph: '*', color });
+        }
+        self
+    }
+
+    /// Only renders the rectangle between both corners, included, instead of the grid bounds.
+    pub fn viewport(mut self, top_left: Pos, bottom_right: Pos) -> Self {
+        self.viewport = Some((top_left, bottom_right));
+        self
+    }
+
+    /// Leaves the ANSI color codes out, e.g. to write the result in a file.
+    pub fn plain(mut self) -> Self {
+        self.colors = false;
+        self
+    }
+
+    fn cell(&self, pos: Pos) -> Cell {
+        self.overlays.get(&pos).copied().unwrap_or_else(|| Cell {
+            glyph: (self.to_char)(self.grid.get(pos)),
+            color: None,
+        })
+    }
+}
+
+impl<'a, G: GridLike<Cell = char>> Render<'a, G> {
+    /// Renders a grid of characters as is, missing cells of a sparse grid being drawn as `.`.
+    pub fn chars(grid: &'a G) -> Self {
+        Render::new(grid, |c| c.copied().unwrap_or('.'))
+    }
+}
+
+impl<G: GridLike> fmt::Display for Render<'_, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((top_left, bottom_right)) = self.viewport.or_else(|| self.grid.bounds()) else {
+            return Ok(());
+        };
+
+        for row in top_left.row..=bottom_right.row {
+            if row != top_left.row {
+                writeln!(f)?;
+            }
+            for col in top_left.col..=bottom_right.col {
+                match self.cell(Pos::new(row, col)) {
+                    Cell { glyph, color: Some(color) } if self.colors => {
+                        write!(f, "\x1b[{}m{glyph}\x1b[0m", color.ansi_code())?
+                    }
+                    Cell { glyph, .. } => write!(f, "{glyph}")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The grid with a path drawn over it in red, e.g. one found by the search module.
+pub fn render_path<G: GridLike<Cell = char>>(grid: &G, path: &[Pos]) -> String {
+    Render::chars(grid).path(path, Some(Color::Red)).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::grid::{Grid, SparseGrid};
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::parse("...\n.#.\n...");
+        let path = [Pos::new(0, 0), Pos::new(0, 1), Pos::new(0, 2), Pos::new(1, 2)];
+
+        assert_eq!(Render::chars(&grid).path(&path, None).to_string(), ">>v\n.#*\n...");
+        assert_eq!(render_path(&grid, &path[2..]), "..\x1b[31mv\x1b[0m\n.#\x1b[31m*\x1b[0m\n...");
+    }
+
+    #[test]
+    fn test_render_viewport() {
+        let mut sparse = SparseGrid::new();
+        sparse.insert(Pos::new(5, 5), 'a');
+        let render = Render::chars(&sparse)
+            .marker(Pos::new(4, 4), 'S', Some(Color::Green))
+            .viewport(Pos::new(4, 4), Pos::new(5, 6))
+            .plain();
+
+        assert_eq!(render.to_string(), "S..\n.a.");
+    }
+}
@@ -0,0 +1,509 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Outcome of a graph search started from a single state.
+///
+/// `distances` holds the cost of the best known path to every state that was reached,
+/// `parents` the predecessor on one of those paths, and `visited` the number of states
+/// that were expanded before the search stopped.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    pub start: S,
+    pub goal: Option<S>,
+    pub distances: HashMap<S, usize>,
+    pub parents: HashMap<S, S>,
+    pub visited: usize,
+}
+
+impl<S: Eq + Hash + Clone> SearchResult<S> {
+    fn new(start: S) -> Self {
+        let mut distances = HashMap::new();
+        distances.insert(start.clone(), 0);
+
+        SearchResult { start, goal: None, distances, parents: HashMap::new(), visited: 0 }
+    }
+
+    /// Cost of the path to the goal, if one was found.
+    pub fn cost(&self) -> Option<usize> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.distances.get(state).copied()
+    }
+
+    /// States from the start to the goal, both included.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|goal| self.path_to(goal))
+    }
+
+    /// States from the start to `state`, both included.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        let mut current = state;
+        while let Some(parent) = self.parents.get(current) {
+            path.push(parent.clone());
+            current = parent;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth-first search where every move costs 1.
+///
+/// Stops at the first state matching `is_goal`; use `|_| false` to explore everything reachable.
+pub fn bfs<S, N, I>(start: S, neighbors: N, is_goal: impl FnMut(&S) -> bool) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    bfs_with_visited(start, neighbors, is_goal, &mut HashSet::new())
+}
+
+/// `bfs` recording the states reached in `visited`, e.g. a grid bitset. States already in it,
+/// the start included, are never reached.
+pub fn bfs_with_visited<S, N, I>(
+    start: S,
+    mut neighbors: N,
+    mut is_goal: impl FnMut(&S) -> bool,
+    visited: &mut impl VisitedSet<S>,
+) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut result = SearchResult::new(start.clone());
+    if !visited.insert(start.clone()) {
+        return result;
+    }
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(state) = queue.pop_front() {
+        result.visited += 1;
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+
+        let distance = result.distances[&state];
+        for next in neighbors(&state) {
+            if visited.insert(next.clone()) {
+                result.distances.insert(next.clone(), distance + 1);
+                result.parents.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    result
+}
+
+/// A set remembering the states a search already went through.
+///
+/// Implemented by `HashSet` for any state, and by the grid bitsets for positions.
+pub trait VisitedSet<S> {
+    /// Returns whether the state was not in the set yet.
+    fn insert(&mut self, state: S) -> bool;
+
+    fn contains(&self, state: &S) -> bool;
+}
+
+impl<S: Eq + Hash> VisitedSet<S> for HashSet<S> {
+    fn insert(&mut self, state: S) -> bool {
+        HashSet::insert(self, state)
+    }
+
+    fn contains(&self, state: &S) -> bool {
+        HashSet::contains(self, state)
+    }
+}
+
+/// Adds to `visited` every state reachable from `start`, and returns how many were added.
+///
+/// States already in `visited` act as walls, so the same set can be reused across several fills.
+pub fn flood_fill<S, N, I>(start: S, mut neighbors: N, visited: &mut impl VisitedSet<S>) -> usize
+where
+    S: Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    if !visited.insert(start.clone()) {
+        return 0;
+    }
+
+    let mut count = 1;
+    let mut stack = vec![start];
+    while let Some(state) = stack.pop() {
+        for next in neighbors(&state) {
+            if visited.insert(next.clone()) {
+                count += 1;
+                stack.push(next);
+            }
+        }
+    }
+
+    count
+}
+
+/// Dijkstra's algorithm, `cost` giving the price of moving from a state to one of its neighbors.
+pub fn dijkstra<S, N, I>(
+    start: S,
+    neighbors: N,
+    cost: impl FnMut(&S, &S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    astar(start, neighbors, cost, |_| 0, is_goal)
+}
+
+/// `dijkstra` recording the expanded states in `visited`, see `astar_with_visited`.
+pub fn dijkstra_with_visited<S, N, I>(
+    start: S,
+    neighbors: N,
+    cost: impl FnMut(&S, &S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+    visited: &mut impl VisitedSet<S>,
+) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    astar_with_visited(start, neighbors, cost, |_| 0, is_goal, visited)
+}
+
+/// A* search. The `heuristic` must never overestimate the remaining cost to a goal, nor drop
+/// by more than the cost of a move, otherwise the returned path may not be the cheapest one.
+pub fn astar<S, N, I>(
+    start: S,
+    neighbors: N,
+    cost: impl FnMut(&S, &S) -> usize,
+    heuristic: impl FnMut(&S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    astar_with_visited(start, neighbors, cost, heuristic, is_goal, &mut HashSet::new())
+}
+
+/// `astar` recording the expanded states in `visited`, e.g. a grid bitset. Every state is
+/// expanded once, and states already in the set, the start included, are never expanded.
+pub fn astar_with_visited<S, N, I>(
+    start: S,
+    mut neighbors: N,
+    mut cost: impl FnMut(&S, &S) -> usize,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+    visited: &mut impl VisitedSet<S>,
+) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut result = SearchResult::new(start.clone());
+    let mut heap = BinaryHeap::new();
+    heap.push(Queued { priority: heuristic(&start), cost: 0, state: start });
+
+    while let Some(Queued { cost: current_cost, state, .. }) = heap.pop() {
+        if current_cost > result.distances[&state] || !visited.insert(state.clone()) {
+            continue;
+        }
+
+        result.visited += 1;
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+
+        for next in neighbors(&state) {
+            if visited.contains(&next) {
+                continue;
+            }
+            let next_cost = current_cost + cost(&state, &next);
+
+            if result.distances.get(&next).is_none_or(|&known| next_cost < known) {
+                result.distances.insert(next.clone(), next_cost);
+                result.parents.insert(next.clone(), state.clone());
+                heap.push(Queued { priority: next_cost + heuristic(&next), cost: next_cost, state: next });
+            }
+        }
+    }
+
+    result
+}
+
+/// Every shortest path from a start state to the cheapest goal states, stored as the DAG
+/// of optimal predecessors. Assumes strictly positive move costs.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<S> {
+    pub start: S,
+    pub goals: Vec<S>,
+    pub distances: HashMap<S, usize>,
+    pub predecessors: HashMap<S, Vec<S>>,
+    pub visited: usize,
+}
+
+impl<S: Eq + Hash + Clone> ShortestPaths<S> {
+    /// Cost of the shortest paths, if a goal was reached.
+    pub fn cost(&self) -> Option<usize> {
+        self.goals.first().map(|goal| self.distances[goal])
+    }
+
+    /// States lying on at least one shortest path, ordered by increasing distance.
+    pub fn states_on_paths(&self) -> Vec<S> {
+        let mut seen: HashSet<S> = self.goals.iter().cloned().collect();
+        let mut stack = self.goals.clone();
+        let mut states = Vec::new();
+
+        while let Some(state) = stack.pop() {
+            for predecessor in self.predecessors.get(&state).into_iter().flatten() {
+                if seen.insert(predecessor.clone()) {
+                    stack.push(predecessor.clone());
+                }
+            }
+            states.push(state);
+        }
+
+        states.sort_by_key(|state| self.distances[state]);
+        states
+    }
+
+    /// Number of distinct shortest paths from the start to any of the goals.
+    pub fn count_paths(&self) -> usize {
+        let mut counts: HashMap<S, usize> = HashMap::new();
+
+        for state in self.states_on_paths() {
+            let count = if state == self.start {
+                1
+            } else {
+                self.predecessors[&state].iter().map(|predecessor| counts[predecessor]).sum()
+            };
+            counts.insert(state, count);
+        }
+
+        self.goals.iter().map(|goal| counts[goal]).sum()
+    }
+
+    /// All shortest paths, each going from the start to a goal.
+    pub fn paths(&self) -> Vec<Vec<S>> {
+        let mut paths = Vec::new();
+        let mut stack: Vec<Vec<S>> = self.goals.iter().map(|goal| vec![goal.clone()]).collect();
+
+        while let Some(reversed_path) = stack.pop() {
+            let last = reversed_path.last().unwrap();
+            if *last == self.start {
+                paths.push(reversed_path.into_iter().rev().collect());
+                continue;
+            }
+
+            for predecessor in &self.predecessors[last] {
+                let mut extended = reversed_path.clone();
+                extended.push(predecessor.clone());
+                stack.push(extended);
+            }
+        }
+
+        paths
+    }
+}
+
+/// Dijkstra's algorithm keeping every optimal predecessor instead of a single parent.
+///
+/// The search keeps going after the first goal so that every goal state reachable at the
+/// same minimal cost is reported.
+pub fn dijkstra_all<S, N, I>(
+    start: S,
+    neighbors: N,
+    cost: impl FnMut(&S, &S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+) -> ShortestPaths<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    dijkstra_all_with_visited(start, neighbors, cost, is_goal, &mut HashSet::new())
+}
+
+/// `dijkstra_all` recording the expanded states in `visited`, e.g. a grid bitset. Costs being
+/// positive, an expanded state cannot gain another optimal predecessor, so it is never looked
+/// at again. States already in the set, the start included, are never expanded.
+pub fn dijkstra_all_with_visited<S, N, I>(
+    start: S,
+    mut neighbors: N,
+    mut cost: impl FnMut(&S, &S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+    visited: &mut impl VisitedSet<S>,
+) -> ShortestPaths<S>
+where
+    S: Eq + Hash + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut result = ShortestPaths {
+        start: start.clone(),
+        goals: Vec::new(),
+        distances: HashMap::from([(start.clone(), 0)]),
+        predecessors: HashMap::new(),
+        visited: 0,
+    };
+    let mut best_cost = None;
+    let mut heap = BinaryHeap::new();
+    heap.push(Queued { priority: 0, cost: 0, state: start });
+
+    while let Some(Queued { cost: current_cost, state, .. }) = heap.pop() {
+        if current_cost > result.distances[&state] || !visited.insert(state.clone()) {
+            continue;
+        }
+        if best_cost.is_some_and(|best| current_cost > best) {
+            break;
+        }
+
+        result.visited += 1;
+        if is_goal(&state) {
+            best_cost = Some(current_cost);
+            result.goals.push(state);
+            continue;
+        }
+
+        for next in neighbors(&state) {
+            if visited.contains(&next) {
+                continue;
+            }
+            let next_cost = current_cost + cost(&state, &next);
+
+            match result.distances.get(&next) {
+                Some(&known) if next_cost > known => {}
+                Some(&known) if next_cost == known => {
+                    result.predecessors.entry(next).or_default().push(state.clone());
+                }
+                _ => {
+                    result.distances.insert(next.clone(), next_cost);
+                    result.predecessors.insert(next.clone(), vec![state.clone()]);
+                    heap.push(Queued { priority: next_cost, cost: next_cost, state: next });
+                }
+            }
+        }
+    }
+
+    result
+}
+
+struct Queued<S> {
+    priority: usize,
+    cost: usize,
+    state: S,
+}
+
+impl<S> PartialEq for Queued<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S> Eq for Queued<S> {}
+
+impl<S> Ord for Queued<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority) // Reverse order for min-heap
+    }
+}
+
+impl<S> PartialOrd for Queued<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::bitset::GridBitSet;
+    use crate::utils::grid_utils::{Pos, CARDINAL_DIRECTIONS};
+
+    fn line_neighbors(n: &i32) -> Vec<i32> {
+        vec![n - 1, n + 1]
+    }
+
+    #[test]
+    fn test_bfs() {
+        let result = bfs(0, line_neighbors, |&n| n == 5);
+        assert_eq!(result.cost(), Some(5));
+        assert_eq!(result.path(), Some(vec![0, 1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let mut visited: HashSet<i32> = HashSet::from([-3, 4]);
+        assert_eq!(flood_fill(0, line_neighbors, &mut visited), 6);
+        assert_eq!(flood_fill(2, line_neighbors, &mut visited), 0);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        // Moving right costs 2, moving left costs 1.
+        let cost = |from: &i32, to: &i32| if to > from { 2 } else { 1 };
+        let result = dijkstra(0, line_neighbors, cost, |&n| n == 3);
+        assert_eq!(result.cost(), Some(6));
+
+        let guided = astar(0, line_neighbors, cost, |&n| 2 * (3 - n).max(0) as usize, |&n| n == 3);
+        assert_eq!(guided.cost(), Some(6));
+        assert_eq!(guided.path(), Some(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn test_with_visited_bitset() {
+        // The 3 x 3 grid with a wall in the middle of the top two rows
+        let mut walls = GridBitSet::new(3, 3);
+        walls.extend([Pos::new(0, 1), Pos::new(1, 1)]);
+        let neighbors = |pos: &Pos| {
+            CARDINAL_DIRECTIONS.into_iter()
+                .map(|direction| pos.neighbor(direction))
+                .filter(|next| (0..3).contains(&next.row) && (0..3).contains(&next.col))
+                .collect::<Vec<_>>()
+        };
+        let (start, end) = (Pos::new(0, 0), Pos::new(0, 2));
+
+        let mut visited = walls.clone();
+        let result = bfs_with_visited(start, neighbors, |&pos| pos == end, &mut visited);
+        assert_eq!(result.cost(), Some(6));
+        assert!(visited.contains(&Pos::new(2, 1)));
+
+        let mut visited = walls.clone();
+        let cost = |_: &Pos, to: &Pos| if to.row == 2 { 3 } else { 1 };
+        assert_eq!(dijkstra_with_visited(start, neighbors, cost, |&pos| pos == end, &mut visited).cost(), Some(12));
+
+        let mut visited = walls;
+        visited.insert(Pos::new(2, 1));
+        assert_eq!(bfs_with_visited(start, neighbors, |&pos| pos == end, &mut visited).cost(), None);
+    }
+
+    #[test]
+    fn test_dijkstra_all() {
+        // Grid moves from (0, 0) to (2, 2), only going right or down.
+        let neighbors = |&(r, c): &(i32, i32)| vec![(r + 1, c), (r, c + 1)].into_iter().filter(|&(r, c)| r <= 2 && c <= 2);
+        let result = dijkstra_all((0, 0), neighbors, |_, _| 1, |&state| state == (2, 2));
+
+        assert_eq!(result.cost(), Some(4));
+        assert_eq!(result.count_paths(), 6);
+        assert_eq!(result.paths().len(), 6);
+        assert_eq!(result.states_on_paths().len(), 9);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::mem;
+
+use crate::utils::cycle::Cycle;
+use crate::utils::grid::GridLike;
+use crate::utils::grid_utils::Pos;
+use crate::utils::recorder::Recorder;
+
+type BufferedUpdate<'a, S> = Box<dyn FnMut(&S, &mut S) + 'a>;
+type StepHook<'a, S> = Box<dyn FnMut(usize, &S) + 'a>;
+
+enum Update<'a, S> {
+    // Reads the current state to write the next one, then both buffers are swapped
+    Buffered(BufferedUpdate<'a, S>, S),
+    InPlace(Box<dyn FnMut(&mut S) + 'a>),
+}
+
+/// A state evolving one step at a time under an update rule, e.g. a grid of cells or a list of entities.
+pub struct Simulation<'a, S> {
+    state: S,
+    step: usize,
+    update: Update<'a, S>,
+    history: VecDeque<S>,
+    history_limit: usize,
+    hooks: Vec<StepHook<'a, S>>,
+}
+
+impl<'a, S: Clone> Simulation<'a, S> {
+    /// Double buffered: `update` reads the current state to fill the next one, which starts as a
+    /// copy of an earlier state. Suits rules where every cell depends on its neighbors, see `cell_rule`.
+    pub fn new(initial: S, update: impl FnMut(&S, &mut S) + 'a) -> Self {
+        let buffer = initial.clone();
+        Simulation::with_update(initial, Update::Buffered(Box::new(update), buffer))
+    }
+
+    /// `update` changes the state in place, e.g. moving every entity in turn.
+    pub fn in_place(initial: S, update: impl FnMut(&mut S) + 'a) -> Self {
+        Simulation::with_update(initial, Update::InPlace(Box::new(update)))
+    }
+
+    fn with_update(state: S, update: Update<'a, S>) -> Self {
+        Simulation { state, step: 0, update, history: VecDeque::new(), history_limit: 0, hooks: Vec::new() }
+    }
+
+    /// Keeps the last `limit` states to `undo` steps.
+    pub fn keep_history(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self
+    }
+
+    /// Calls `hook` with the step number and the state after every step.
+    pub fn on_step(mut self, hook: impl FnMut(usize, &S) + 'a) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Records the current state then the one after every step, rendered by `render`.
+    pub fn recording(self, recorder: &'a mut Recorder, render: impl Fn(&S) -> String + 'a) -> Self {
+        recorder.record(self.step, render(&self.state));
+        self.on_step(move |step, state| recorder.record(step, render(state)))
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Number of steps made so far.
+    pub fn step_count(&self) -> usize {
+        self.step
+    }
+
+    /// States kept by `keep_history`, the oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &S> {
+        self.history.iter()
+    }
+
+    pub fn step(&mut self) -> &S {
+        if self.history_limit > 0 {
+            if self.history.len() == self.history_limit {
+                self.history.pop_front();
+            }
+            self.history.push_back(self.state.clone());
+        }
+
+        match &mut self.update {
+            Update::Buffered(update, buffer) => {
+                update(&self.state, buffer);
+                mem::swap(&mut self.state, buffer);
+            }
+            Update::InPlace(update) => update(&mut self.state),
+        }
+        self.step += 1;
+
+        for hook in self.hooks.iter_mut() {
+            hook(self.step, &self.state);
+        }
+        &self.state
+    }
+
+    pub fn run(&mut self, steps: usize) -> &S {
+        for _ in 0..steps {
+            self.step();
+        }
+        &self.state
+    }
+
+    /// Steps until `done` holds, at most `limit` more times. Returns the step count, from the start
+    /// of the simulation, when it does.
+    pub fn run_until(&mut self, limit: usize, done: impl Fn(&S) -> bool) -> Option<usize> {
+        for taken in 0..=limit {
+            if done(&self.state) {
+                return Some(self.step);
+            }
+            if taken == limit {
+                break;
+            }
+            self.step();
+        }
+        None
+    }
+
+    /// Goes back to the state before the last step, if the history still holds it. Frames
+    /// already given to the hooks are kept.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop_back() {
+            Some(previous) => {
+                self.state = previous;
+                self.step -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<S: Clone + PartialEq> Simulation<'_, S> {
+    /// Steps until a step leaves the state unchanged, at most `limit` more times. Returns the number
+    /// of steps that changed it, or `None` if it never settles, e.g. when oscillating.
+    pub fn run_until_stable(&mut self, limit: usize) -> Option<usize> {
+        let start = self.step;
+        for _ in 0..limit {
+            let previous = self.state.clone();
+            if *self.step() == previous {
+                return Some(self.step - start - 1);
+            }
+        }
+        None
+    }
+}
+
+impl<S: Clone + Hash + Eq> Simulation<'_, S> {
+    /// Steps until a state comes back, counting steps from the start of the simulation. Returns
+    /// the cycle along with the states seen since the current one, by step.
+    fn cycle_with_states(&mut self) -> (Cycle, Vec<S>) {
+        let first = self.step;
+        let mut seen: HashMap<S, usize> = HashMap::new();
+        let mut states = Vec::new();
+
+        loop {
+            if let Some(&start) = seen.get(&self.state) {
+                return (Cycle { start, length: self.step - start }, states);
+            }
+            seen.insert(self.state.clone(), self.step);
+            states.push(self.state.clone());
+            debug_assert_eq!(states.len(), self.step - first + 1);
+            self.step();
+        }
+    }
+
+    /// Steps until a state comes back, steps being counted from the start of the simulation.
+    pub fn find_cycle(&mut self) -> Cycle {
+        self.cycle_with_states().0
+    }
+
+    /// Jumps to `step`, e.g. a billion, skipping the repetitions of the cycle the states enter.
+    /// Hooks only see the steps actually simulated.
+    pub fn skip_to(&mut self, step: usize) -> &S {
+        let first = self.step;
+        if step < first {
+            return &self.state;
+        }
+
+        let (cycle, mut states) = self.cycle_with_states();
+        if step < self.step {
+            self.state = states.swap_remove(step - first);
+        } else {
+            self.state = states.swap_remove(cycle.equivalent_step(step) - first);
+        }
+        self.step = step;
+        self.history.clear();
+        &self.state
+    }
+}
+
+/// Per-cell update for `Simulation::new`: every cell of the next grid becomes `rule` applied to
+/// the current grid, the cell position and its value. A sparse grid keeps the cells it has.
+pub fn cell_rule<'a, G: GridLike>(rule: impl Fn(&G, Pos, &G::Cell) -> G::Cell + 'a) -> impl FnMut(&G, &mut G) + 'a {
+    move |current, next| {
+        let Some((top_left, bottom_right)) = current.bounds() else {
+            return;
+        };
+
+        for row in top_left.row..=bottom_right.row {
+            for col in top_left.col..=bottom_right.col {
+                let pos = Pos::new(row, col);
+                if let (Some(cell), Some(target)) = (current.get(pos), next.get_mut(pos)) {
+                    *target = rule(current, pos, cell);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::grid::Grid;
+    use crate::utils::grid_utils::ALL_DIRECTIONS;
+
+    fn life(grid: &Grid<char>, pos: Pos, &cell: &char) -> char {
+        let alive = grid.neighbors(pos, &ALL_DIRECTIONS).into_iter().filter(|&n| grid[n] == '#').count();
+        match (cell, alive) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn test_cellular_automaton() {
+        let blinker = Grid::parse(".....\n..#..\n..#..\n..#..\n.....");
+        let mut simulation = Simulation::new(blinker.clone(), cell_rule(life)).keep_history(2);
+
+        assert_eq!(simulation.step().render(|cell| *cell.unwrap()), ".....\n.....\n.###.\n.....\n.....");
+        simulation.step();
+        assert_eq!(*simulation.state(), blinker);
+        assert!(simulation.undo() && simulation.undo() && !simulation.undo());
+        assert_eq!(simulation.step_count(), 0);
+
+        assert_eq!(simulation.find_cycle(), Cycle { start: 0, length: 2 });
+        assert_eq!(*simulation.skip_to(1_000_001), Grid::parse(".....\n.....\n.###.\n.....\n....."));
+
+        // Oscillates forever, never stable
+        let mut oscillating = Simulation::new(blinker.clone(), cell_rule(life));
+        assert_eq!(oscillating.run_until_stable(10), None);
+        assert_eq!(oscillating.step_count(), 10);
+
+        let mut recorder = Recorder::new();
+        Simulation::new(blinker, cell_rule(life))
+            .recording(&mut recorder, |grid| grid.render(|cell| *cell.unwrap()))
+            .run(3);
+        assert_eq!(recorder.len(), 4);
+        assert_eq!(recorder.frames()[3].text, recorder.frames()[1].text);
+    }
+
+    #[test]
+    fn test_in_place() {
+        let mut simulation = Simulation::in_place(vec![10, 3], |values: &mut Vec<i32>| {
+            values.iter_mut().for_each(|value| *value = (*value - 1).max(0));
+        });
+        assert_eq!(simulation.run_until(100, |values| values[1] == 0), Some(3));
+        assert_eq!(simulation.run_until_stable(100), Some(7));
+        assert_eq!(*simulation.state(), vec![0, 0]);
+
+        // The limit counts the steps of each call, not from the start
+        let mut counter = Simulation::in_place(0, |count: &mut usize| *count += 1);
+        counter.run(5);
+        assert_eq!(counter.run_until(3, |_| false), None);
+        assert_eq!(counter.step_count(), 8);
+        assert_eq!(counter.run_until(2, |&count| count == 10), Some(10));
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::utils::grid_utils::Pos;
+
+/// Disjoint sets of the indices `0..len`, with path compression and union by rank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    /// `len` elements, each in its own set.
+    pub fn new(len: usize) -> Self {
+        UnionFind { parent: (0..len).collect(), rank: vec![0; len], size: vec![1; len], components: len }
+    }
+
+    /// Adds an element in its own set, returning its index.
+    pub fn push(&mut self) -> usize {
+        let index = self.parent.len();
+        self.parent.push(index);
+        self.rank.push(0);
+        self.size.push(1);
+        self.components += 1;
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Representative of the set holding `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Path compression: every element on the way now points to the root
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`. Returns whether they were disjoint.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // Union by rank: the shallower tree goes under the deeper one
+        let (root, child) = if self.rank[a] < self.rank[b] { (b, a) } else { (a, b) };
+        if self.rank[root] == self.rank[child] {
+            self.rank[root] += 1;
+        }
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set holding `x`.
+    pub fn component_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Size of every set, in no particular order.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&x| self.parent[x] == x)
+            .map(|root| self.size[root])
+            .collect()
+    }
+}
+
+/// Disjoint sets of any hashable keys, a key being added the first time it is used.
+#[derive(Debug, Clone)]
+pub struct KeyedUnionFind<K> {
+    indices: HashMap<K, usize>,
+    sets: UnionFind,
+}
+
+impl<K: Hash + Eq> Default for KeyedUnionFind<K> {
+    fn default() -> Self {
+        KeyedUnionFind::new()
+    }
+}
+
+impl<K: Hash + Eq> KeyedUnionFind<K> {
+    pub fn new() -> Self {
+        KeyedUnionFind { indices: HashMap::new(), sets: UnionFind::new(0) }
+    }
+
+    /// Adds `key` in its own set if it is new. Returns its index in the underlying `UnionFind`.
+    pub fn insert(&mut self, key: K) -> usize {
+        let sets = &mut self.sets;
+        *self.indices.entry(key).or_insert_with(|| sets.push())
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    /// Merges the sets holding `a` and `b`. Returns whether they were disjoint.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.sets.union(a, b)
+    }
+
+    /// Whether both keys are known and in the same set.
+    pub fn connected(&mut self, a: &K, b: &K) -> bool {
+        match (self.indices.get(a), self.indices.get(b)) {
+            (Some(&a), Some(&b)) => self.sets.connected(a, b),
+            _ => false,
+        }
+    }
+
+    /// Number of keys in the set holding `key`, 0 for an unknown key.
+    pub fn component_size(&mut self, key: &K) -> usize {
+        self.indices.get(key).map_or(0, |&index| self.sets.component_size(index))
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.sets.component_count()
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.sets.component_sizes()
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+/// Disjoint sets of the positions of a `width` x `height` grid, every cell starting on its own.
+///
+/// Positions outside of the bounds are never connected, and joining one panics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridUnionFind {
+    sets: UnionFind,
+    width: usize,
+    height: usize,
+}
+
+impl GridUnionFind {
+    pub fn new(width: usize, height: usize) -> Self {
+        GridUnionFind { sets: UnionFind::new(width * height), width, height }
+    }
+
+    /// Sets with the bounds of a `Vec<Vec<_>>` grid.
+    pub fn for_grid<T>(grid: &[Vec<T>]) -> Self {
+        GridUnionFind::new(grid.first().map_or(0, |row| row.len()), grid.len())
+    }
+
+    pub fn index_of(&self, pos: Pos) -> Option<usize> {
+        let row = usize::try_from(pos.row).ok().filter(|&row| row < self.height)?;
+        let col = usize::try_from(pos.col).ok().filter(|&col| col < self.width)?;
+        Some(row * self.width + col)
+    }
+
+    pub fn pos_of(&self, index: usize) -> Pos {
+        Pos::new((index / self.width) as isize, (index % self.width) as isize)
+    }
+
+    fn expect_index(&self, pos: Pos) -> usize {
+        self.index_of(pos).unwrap_or_else(|| panic!("{pos:?} is outside of the grid bounds"))
+    }
+
+    /// Merges the sets holding `a` and `b`. Returns whether they were disjoint.
+    pub fn union(&mut self, a: Pos, b: Pos) -> bool {
+        let (a, b) = (self.expect_index(a), self.expect_index(b));
+        self.sets.union(a, b)
+    }
+
+    pub fn connected(&mut self, a: Pos, b: Pos) -> bool {
+        match (self.index_of(a), self.index_of(b)) {
+            (Some(a), Some(b)) => self.sets.connected(a, b),
+            _ => false,
+        }
+    }
+
+    /// Representative position of the set holding `pos`.
+    pub fn find(&mut self, pos: Pos) -> Option<Pos> {
+        let index = self.index_of(pos)?;
+        let root = self.sets.find(index);
+        Some(self.pos_of(root))
+    }
+
+    /// Number of cells in the set holding `pos`, 0 outside of the grid.
+    pub fn component_size(&mut self, pos: Pos) -> usize {
+        self.index_of(pos).map_or(0, |index| self.sets.component_size(index))
+    }
+
+    /// Number of disjoint sets, counting every cell left on its own.
+    pub fn component_count(&self) -> usize {
+        self.sets.component_count()
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.sets.component_sizes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 1));
+        assert!(!sets.union(0, 2));
+        sets.union(3, 4);
+
+        assert!(sets.connected(0, 2) && !sets.connected(2, 3));
+        assert_eq!((sets.component_size(1), sets.component_count()), (3, 3));
+
+        let mut sizes = sets.component_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_keyed_and_grid() {
+        let mut sets = KeyedUnionFind::new();
+        sets.union("ka", "co");
+        sets.union("co", "ta");
+        sets.insert("yn");
+        assert!(sets.connected(&"ka", &"ta") && !sets.connected(&"ka", &"yn"));
+        assert_eq!((sets.component_size(&"ta"), sets.component_count()), (3, 2));
+
+        let mut grid = GridUnionFind::new(3, 2);
+        grid.union(Pos::new(0, 0), Pos::new(1, 0));
+        grid.union(Pos::new(1, 0), Pos::new(1, 2));
+        assert!(grid.connected(Pos::new(0, 0), Pos::new(1, 2)));
+        assert!(!grid.connected(Pos::new(0, 0), Pos::new(5, 5)));
+        assert_eq!((grid.component_size(Pos::new(0, 0)), grid.component_count()), (3, 4));
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::utils::parse::{comma_list, ints_array, key_values, sections_array, ParseError};
+
+/// Instructions of the 3-bit computer, by opcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    pub fn from_bits(bits: u8) -> Opcode {
+        match bits & 7 {
+            0 => Opcode::Adv,
+            1 => Opcode::Bxl,
+            2 => Opcode::Bst,
+            3 => Opcode::Jnz,
+            4 => Opcode::Bxc,
+            5 => Opcode::Out,
+            6 => Opcode::Bdv,
+            _ => Opcode::Cdv,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+
+    /// Whether the operand is a combo one, i.e. may designate a register.
+    pub fn takes_combo(&self) -> bool {
+        matches!(self, Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv)
+    }
+}
+
+/// A combo operand: a literal up to 3, or the value of a register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Combo {
+    Literal(u8),
+    A,
+    B,
+    C,
+    Reserved,
+}
+
+impl Combo {
+    pub fn from_bits(bits: u8) -> Combo {
+        match bits & 7 {
+            4 => Combo::A,
+            5 => Combo::B,
+            6 => Combo::C,
+            7 => Combo::Reserved,
+            literal => Combo::Literal(literal),
+        }
+    }
+}
+
+impl Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Combo::Literal(literal) => write!(f, "{literal}"),
+            Combo::A => f.write_str("a"),
+            Combo::B => f.write_str("b"),
+            Combo::C => f.write_str("c"),
+            Combo::Reserved => f.write_str("?"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub address: usize,
+    pub opcode: Opcode,
+    pub operand: u8,
+}
+
+impl Instruction {
+    /// What the instruction does, as pseudo code, e.g. `b = a % 8`.
+    pub fn explain(&self) -> String {
+        let combo = Combo::from_bits(self.operand);
+        let literal = self.operand;
+        match self.opcode {
+            Opcode::Adv => format!("a = a >> {combo}"),
+            Opcode::Bxl => format!("b = b ^ {literal}"),
+            Opcode::Bst => format!("b = {combo} % 8"),
+            Opcode::Jnz => format!("if a != 0 goto {literal}"),
+            Opcode::Bxc => "b = b ^ c".to_string(),
+            Opcode::Out => format!("output {combo} % 8"),
+            Opcode::Bdv => format!("b = a >> {combo}"),
+            Opcode::Cdv => format!("c = a >> {combo}"),
+        }
+    }
+}
+
+impl Display for Instruction {
+    /// The mnemonic and operand, combo operands being shown as the register they read, e.g. `bst a`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = self.opcode.mnemonic();
+        match self.opcode {
+            Opcode::Bxc => f.write_str(mnemonic),
+            opcode if opcode.takes_combo() => write!(f, "{mnemonic} {}", Combo::from_bits(self.operand)),
+            _ => write!(f, "{mnemonic} {}", self.operand),
+        }
+    }
+}
+
+/// Every instruction of the program, an opcode without operand at the end being left out.
+pub fn disassemble(program: &[u8]) -> Vec<Instruction> {
+    program.chunks_exact(2)
+        .enumerate()
+        .map(|(i, pair)| Instruction { address: 2 * i, opcode: Opcode::from_bits(pair[0]), operand: pair[1] })
+        .collect()
+}
+
+/// One line per instruction with its address, e.g. `  2: bxl 5      ; b = b ^ 5`.
+pub fn disassembly(program: &[u8]) -> String {
+    disassemble(program).iter()
+        .map(|instruction| format!("{:>3}: {:<10} ; {}", instruction.address, instruction.to_string(), instruction.explain()))
+        .join("\n")
+}
+
+/// A program that cannot go on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmError {
+    /// The instruction at `address` uses the combo operand 7.
+    ReservedOperand { address: usize },
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VmError::ReservedOperand { address } => write!(f, "reserved combo operand 7 at address {address}"),
+        }
+    }
+}
+
+impl Error for VmError {}
+
+/// Result of a single step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Continue,
+    Output(u8),
+    Halted,
+}
+
+/// Why `resume` stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Breakpoint(usize),
+    Halted,
+}
+
+/// An executed instruction with the registers before and after, shown in octal as each output
+/// usually comes from 3 bits of a register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub instruction: Instruction,
+    pub before: [u64; 3],
+    pub after: [u64; 3],
+    pub event: Event,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.after;
+        write!(f, "{:>3}: {:<10} a={a:#o} b={b:#o} c={c:#o}", self.instruction.address, self.instruction.to_string())?;
+        if let Event::Output(value) = self.event {
+            write!(f, " -> {value}")?;
+        }
+        Ok(())
+    }
+}
+
+// `a` divided by 2 to the power of `shift`
+fn shifted(a: u64, shift: u64) -> u64 {
+    u32::try_from(shift).ok().and_then(|shift| a.checked_shr(shift)).unwrap_or(0)
+}
+
+/// The 3-bit computer: three registers, a program of 3-bit numbers and the values it outputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vm {
+    program: Vec<u8>,
+    initial: [u64; 3],
+    pub registers: [u64; 3],
+    pub ip: usize,
+    output: Vec<u8>,
+    breakpoints: HashSet<usize>,
+}
+
+impl FromStr for Vm {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Vm::parse(input)
+    }
+}
+
+impl Vm {
+    pub fn new(program: Vec<u8>, registers: [u64; 3]) -> Self {
+        Vm { program, initial: registers, registers, ip: 0, output: Vec::new(), breakpoints: HashSet::new() }
+    }
+
+    /// Parses `Register A: 729` lines for A, B and C, then `Program: 0,1,5,4,3,0` after a blank line.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let [registers, program] = sections_array(input)?;
+
+        let registers = registers.parse(|text| {
+            let values = key_values(text)?;
+            let mut registers = [0; 3];
+            for (register, name) in registers.iter_mut().zip(["Register A", "Register B", "Register C"]) {
+                let value = values.get(name).ok_or_else(|| ParseError::at(text, 0, format!("expected the `{name}: ...` line")))?;
+                let offset = text.find(name).map_or(0, |line| line + text[line..].find(value).unwrap_or(0));
+                *register = u64::try_from(ints_array::<1>(value)?[0])
+                    .map_err(|_| ParseError::at(text, offset, format!("`{name}` cannot be negative, got {value}")))?;
+            }
+            Ok(registers)
+        })?;
+
+        let program = program.parse(|text| {
+            let program = key_values(text)?.get("Program").copied()
+                .ok_or_else(|| ParseError::at(text, 0, "expected the `Program: ...` line"))?;
+            let offset = text.find(program).unwrap_or(0);
+            let numbers: Vec<u8> = comma_list(program).map_err(|e| ParseError::at(text, offset + e.column - 1, e.message))?;
+            match numbers.iter().position(|&n| n > 7) {
+                Some(i) => Err(ParseError::at(text, offset, format!("program value {} at index {i} is not 3-bit", numbers[i]))),
+                None => Ok(numbers),
+            }
+        })?;
+
+        Ok(Vm::new(program, registers))
+    }
+
+    pub fn program(&self) -> &[u8] {
+        &self.program
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// The output as comma separated values.
+    pub fn output_string(&self) -> String {
+        self.output.iter().join(",")
+    }
+
+    /// Starts over with the initial registers, register A being set to `a`.
+    pub fn reset(&mut self, a: u64) {
+        self.registers = [a, self.initial[1], self.initial[2]];
+        self.ip = 0;
+        self.output.clear();
+    }
+
+    pub fn halted(&self) -> bool {
+        self.ip + 1 >= self.program.len()
+    }
+
+    /// The instruction about to run.
+    pub fn current(&self) -> Option<Instruction> {
+        (!self.halted()).then(|| Instruction {
+            address: self.ip,
+            opcode: Opcode::from_bits(self.program[self.ip]),
+            operand: self.program[self.ip + 1],
+        })
+    }
+
+    fn combo(&self, instruction: &Instruction) -> Result<u64, VmError> {
+        match Combo::from_bits(instruction.operand) {
+            Combo::Literal(literal) => Ok(literal as u64),
+            Combo::A => Ok(self.registers[0]),
+            Combo::B => Ok(self.registers[1]),
+            Combo::C => Ok(self.registers[2]),
+            Combo::Reserved => Err(VmError::ReservedOperand { address: instruction.address }),
+        }
+    }
+
+    pub fn step(&mut self) -> Result<Event, VmError> {
+        let Some(instruction) = self.current() else {
+            return Ok(Event::Halted);
+        };
+        let literal = instruction.operand as u64;
+        let [a, b, c] = self.registers;
+        let mut event = Event::Continue;
+
+        match instruction.opcode {
+            Opcode::Adv => self.registers[0] = shifted(a, self.combo(&instruction)?),
+            Opcode::Bxl => self.registers[1] = b ^ literal,
+            Opcode::Bst => self.registers[1] = self.combo(&instruction)? % 8,
+            Opcode::Jnz if a != 0 => {
+                self.ip = literal as usize;
+                return Ok(event);
+            }
+            Opcode::Jnz => {}
+            Opcode::Bxc => self.registers[1] = b ^ c,
+            Opcode::Out => {
+                let value = (self.combo(&instruction)? % 8) as u8;
+                self.output.push(value);
+                event = Event::Output(value);
+            }
+            Opcode::Bdv => self.registers[1] = shifted(a, self.combo(&instruction)?),
+            Opcode::Cdv => self.registers[2] = shifted(a, self.combo(&instruction)?),
+        }
+
+        self.ip += 2;
+        Ok(event)
+    }
+
+    /// Runs until the program halts, which it may never do, returning the whole output.
+    pub fn run(&mut self) -> Result<&[u8], VmError> {
+        while !self.halted() {
+            self.step()?;
+        }
+        Ok(&self.output)
+    }
+
+    /// Runs until the next value is output, `None` if the program halts first.
+    pub fn next_output(&mut self) -> Result<Option<u8>, VmError> {
+        loop {
+            match self.step()? {
+                Event::Output(value) => return Ok(Some(value)),
+                Event::Halted => return Ok(None),
+                Event::Continue => {}
+            }
+        }
+    }
+
+    /// Stops `resume` before running the instruction at `address`.
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn remove_breakpoint(&mut self, address: usize) -> bool {
+        self.breakpoints.remove(&address)
+    }
+
+    /// Runs at least one instruction, then until a breakpoint is reached or the program halts.
+    pub fn resume(&mut self) -> Result<Stop, VmError> {
+        loop {
+            if self.step()? == Event::Halted || self.halted() {
+                return Ok(Stop::Halted);
+            }
+            if self.breakpoints.contains(&self.ip) {
+                return Ok(Stop::Breakpoint(self.ip));
+            }
+        }
+    }
+
+    /// Runs at most `limit` instructions, recording each of them.
+    pub fn trace(&mut self, limit: usize) -> Result<Vec<TraceEntry>, VmError> {
+        let mut trace = Vec::new();
+        while trace.len() < limit {
+            let Some(instruction) = self.current() else {
+                break;
+            };
+            let before = self.registers;
+            let event = self.step()?;
+            trace.push(TraceEntry { instruction, before, after: self.registers, event });
+        }
+        Ok(trace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+
+    #[test]
+    fn test_run() {
+        let mut vm = Vm::parse(EXAMPLE).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.output_string(), "4,6,3,5,6,3,5,2,1,0");
+
+        let mut vm = Vm::new(vec![2, 6], [0, 0, 9]);
+        vm.run().unwrap();
+        assert_eq!(vm.registers[1], 1);
+        assert_eq!(Vm::new(vec![0, 7], [0; 3]).step(), Err(VmError::ReservedOperand { address: 0 }));
+        assert!(Vm::parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 0,8").is_err());
+
+        let negative = Vm::parse("Register A: 1\nRegister B: -1\nRegister C: 0\n\nProgram: 0,1").unwrap_err();
+        assert_eq!((negative.line, negative.column), (2, 13));
+    }
+
+    #[test]
+    fn test_debugging() {
+        let mut vm = Vm::parse(EXAMPLE).unwrap();
+        vm.add_breakpoint(4);
+        assert_eq!(vm.resume(), Ok(Stop::Breakpoint(4)));
+        assert_eq!(vm.output(), &[4]);
+        assert_eq!(vm.current().map(|instruction| instruction.to_string()), Some("jnz 0".to_string()));
+
+        let trace = vm.trace(3).unwrap();
+        assert_eq!(trace.iter().map(|entry| entry.instruction.address).collect::<Vec<_>>(), vec![4, 0, 2]);
+        assert_eq!(trace[2].to_string(), "  2: out a      a=0o266 b=0o0 c=0o0 -> 6");
+
+        vm.remove_breakpoint(4);
+        assert_eq!(vm.resume(), Ok(Stop::Halted));
+        assert_eq!(vm.output().len(), 10);
+    }
+
+    #[test]
+    fn test_disassembly() {
+        assert_eq!(
+            disassembly(&[2, 4, 1, 5, 5, 5, 4, 3, 0, 3, 3, 0]),
+            [
+                "  0: bst a      ; b = a % 8",
+                "  2: bxl 5      ; b = b ^ 5",
+                "  4: out b      ; output b % 8",
+                "  6: bxc        ; b = b ^ c",
+                "  8: adv 3      ; a = a >> 3",
+                " 10: jnz 0      ; if a != 0 goto 0",
+            ].join("\n")
+        );
+    }
+}